@@ -1,25 +1,32 @@
 //! # Cache Module
 //!
-//! Provides scan result caching functionality to improve performance
-//! when re-scanning the same directories.
+//! Persistent, per-directory scan index that makes re-scans incremental.
 //!
 //! ## Features
 //!
-//! - Caches scan results for 5 minutes
-//! - Validates cache against scan parameters
-//! - Filters out non-existent items
-//! - Stores cache in user's home directory
+//! - Remembers every scanned directory's mtime and what it contained
+//! - Remembers artifact sizes along with a signature of their contents
+//! - Entries are keyed by absolute path, so scanning a child path reuses
+//!   what a scan of its parent learned
+//! - Entries stay valid as long as the filesystem says nothing changed;
+//!   ones not seen for `cache.max_age` (30 days by default) are dropped
+//! - Stores the index in user's home directory
 
-use crate::types::{DeletableItem, ScanCache};
+use crate::rules::rules_path;
+use crate::types::{DeletableItem, ScanIndex};
 use crate::utils::format_size;
 use colored::*;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-/// Cache validity duration in seconds (5 minutes)
-const CACHE_VALIDITY_SECONDS: u64 = 300;
+/// Bumped whenever the index format or its invalidation rules change
+const INDEX_VERSION: u32 = 3;
+
+/// Changes within this window of a verification may not show up in mtimes,
+/// so such entries are never trusted (the "racy git" problem)
+const RACY_WINDOW: Duration = Duration::from_secs(2);
 
 /// Returns the path to the cache file in the user's home directory.
 ///
@@ -48,100 +55,122 @@ pub fn get_cache_path() -> PathBuf {
         .join(".gigabroom-cache.json")
 }
 
-/// Loads cached scan results if valid and matching scan parameters.
-///
-/// The cache is considered valid if:
-/// - It exists and is readable
-/// - Scan path and max depth match
-/// - Cache is less than 5 minutes old
-/// - Cached items still exist on filesystem
-///
-/// # Arguments
-///
-/// * `scan_path` - The directory path that was scanned
-/// * `max_depth` - The maximum recursion depth used
+/// Modification time of the user rules file, if any
+fn rules_stamp() -> Option<SystemTime> {
+    fs::metadata(rules_path()).and_then(|m| m.modified()).ok()
+}
+
+/// Check whether a cached timestamp still describes the filesystem
 ///
-/// # Returns
+/// `current` must equal `cached`, and the entry must have been verified
+/// comfortably after that timestamp.
+pub fn unchanged_since(current: SystemTime, cached: SystemTime, verified: SystemTime) -> bool {
+    current == cached
+        && verified
+            .duration_since(cached)
+            .map(|d| d >= RACY_WINDOW)
+            .unwrap_or(false)
+}
+
+/// Loads the scan index.
 ///
-/// `Some(Vec<DeletableItem>)` if cache is valid, `None` otherwise
+/// Returns an empty index if there is none, it can't be read, or it was
+/// written by an incompatible version. Directory listings are discarded when
+/// the user rules changed, since they decide what counts as deletable.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use std::path::Path;
-/// use gigabroom::cache::load_cache;
+/// use gigabroom::cache::load_index;
 ///
-/// let items = load_cache(Path::new("/home/user/projects"), 10);
-/// if let Some(cached) = items {
-///     println!("Loaded {} items from cache", cached.len());
-/// }
+/// let index = load_index();
+/// println!("{} directories indexed", index.dirs.len());
 /// ```
-pub fn load_cache(scan_path: &Path, max_depth: usize) -> Option<Vec<DeletableItem>> {
-    let cache_path = get_cache_path();
+pub fn load_index() -> ScanIndex {
+    let mut index = fs::read_to_string(get_cache_path())
+        .ok()
+        .and_then(|data| serde_json::from_str::<ScanIndex>(&data).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or_default();
 
-    if !cache_path.exists() {
-        return None;
+    let stamp = rules_stamp();
+    if index.rules_stamp != stamp {
+        index.dirs.clear();
+        index.rules_stamp = stamp;
     }
 
-    let cache_data = fs::read_to_string(&cache_path).ok()?;
-    let cache: ScanCache = serde_json::from_str(&cache_data).ok()?;
-
-    // Validate cache matches current scan parameters
-    if cache.scan_path != scan_path || cache.max_depth != max_depth {
-        return None;
-    }
-
-    // Check if cache is still valid (< 5 minutes old)
-    if let Ok(elapsed) = cache.scan_time.elapsed() {
-        if elapsed.as_secs() > CACHE_VALIDITY_SECONDS {
-            return None;
-        }
-    }
-
-    // Filter out items that no longer exist
-    let valid_items: Vec<DeletableItem> = cache
-        .items
-        .into_iter()
-        .filter(|item| item.path.exists())
-        .collect();
-
-    Some(valid_items)
+    index
 }
 
-/// Saves scan results to cache for future use.
-///
-/// Creates a JSON cache file in the user's home directory containing
-/// the scan path, max depth, timestamp, and found items.
-///
-/// # Arguments
+/// Saves the scan index for future scans.
 ///
-/// * `scan_path` - The directory path that was scanned
-/// * `max_depth` - The maximum recursion depth used
-/// * `items` - The scan results to cache
+/// Entries that haven't been confirmed for `cache.max_age` (30 days by
+/// default) are dropped first.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use std::path::Path;
-/// use gigabroom::cache::save_cache;
+/// use gigabroom::cache::{load_index, save_index};
 ///
-/// let items = vec![]; // Your scan results
-/// save_cache(Path::new("/home/user/projects"), 10, &items);
+/// let mut index = load_index();
+/// // ... scan ...
+/// save_index(&mut index);
 /// ```
-pub fn save_cache(scan_path: &Path, max_depth: usize, items: &[DeletableItem]) {
-    let cache = ScanCache {
-        scan_path: scan_path.to_path_buf(),
-        scan_time: SystemTime::now(),
-        items: items.to_vec(),
-        max_depth,
+pub fn save_index(index: &mut ScanIndex) {
+    let retention = crate::config::get().cache_max_age();
+    let now = SystemTime::now();
+    let fresh = |verified: &SystemTime| {
+        now.duration_since(*verified)
+            .map(|age| age < retention)
+            .unwrap_or(true)
     };
 
-    if let Ok(json) = serde_json::to_string(&cache) {
+    index.dirs.retain(|_, dir| fresh(&dir.verified));
+    index.sizes.retain(|_, size| fresh(&size.verified));
+    index.version = INDEX_VERSION;
+    index.rules_stamp = rules_stamp();
+
+    if let Ok(json) = serde_json::to_string(index) {
+        // Write beside the index and swap it in, so a concurrent run or a
+        // crash never leaves a half-written index behind
         let cache_path = get_cache_path();
-        let _ = fs::write(cache_path, json);
+        let tmp_path = cache_path.with_extension(format!("tmp.{}", std::process::id()));
+        if fs::write(&tmp_path, json).and_then(|_| fs::rename(&tmp_path, &cache_path)).is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
     }
 }
 
+/// Forget everything the index knows about deleted or trimmed items.
+///
+/// Drops entries at or below each item and the listing of its parent.
+/// Mtime checks would catch these changes too; this keeps the index small.
+pub fn forget_items(items: &[&DeletableItem]) {
+    if items.is_empty() || !get_cache_path().exists() {
+        return;
+    }
+
+    let mut index = load_index();
+    // Keys are canonical; the items themselves may be gone, so resolve their parents
+    let paths: Vec<PathBuf> = items
+        .iter()
+        .filter_map(|item| {
+            let parent = item.path.parent()?.canonicalize().ok()?;
+            Some(parent.join(item.path.file_name()?))
+        })
+        .collect();
+
+    let affected = |key: &Path| {
+        paths
+            .iter()
+            .any(|path| key.starts_with(path) || path.parent() == Some(key))
+    };
+
+    index.dirs.retain(|key, _| !affected(key));
+    index.sizes.retain(|key, _| !affected(key));
+    save_index(&mut index);
+}
+
 /// Deletes the cache file from the filesystem.
 ///
 /// Silently succeeds even if the cache file doesn't exist.
@@ -159,9 +188,18 @@ pub fn clear_cache() {
     let _ = fs::remove_file(cache_path);
 }
 
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{} seconds", s),
+        s if s < 3600 => format!("{} minutes", s / 60),
+        s if s < 86400 => format!("{} hours", s / 3600),
+        s => format!("{} days", s / 86400),
+    }
+}
+
 /// Displays detailed information about the cache.
 ///
-/// Shows cache location, size, age, scan parameters, and validity status.
+/// Shows cache location, size, how much it holds and when it was last used.
 /// Outputs formatted information to stdout using colored text.
 ///
 /// # Examples
@@ -190,41 +228,47 @@ pub fn show_cache_info() {
                 format_size(metadata.len())
             );
 
-            if let Ok(cache_data) = fs::read_to_string(&cache_path) {
-                if let Ok(cache) = serde_json::from_str::<ScanCache>(&cache_data) {
-                    println!(
-                        "  {}: {}",
-                        "Scan path".bright_white(),
-                        cache.scan_path.display()
-                    );
-                    println!("  {}: {}", "Max depth".bright_white(), cache.max_depth);
-                    println!("  {}: {}", "Items cached".bright_white(), cache.items.len());
-
-                    if let Ok(elapsed) = cache.scan_time.elapsed() {
-                        let secs = elapsed.as_secs();
-                        let age = match secs {
-                            s if s < 60 => format!("{} seconds", s),
-                            s if s < 3600 => format!("{} minutes", s / 60),
-                            s if s < 86400 => format!("{} hours", s / 3600),
-                            s => format!("{} days", s / 86400),
-                        };
-                        println!("  {}: {} ago", "Cache age".bright_white(), age);
-
-                        if secs > CACHE_VALIDITY_SECONDS {
-                            println!(
-                                "  {}",
-                                format!("Cache is stale (>{} minutes old)", CACHE_VALIDITY_SECONDS / 60)
-                                    .yellow()
-                            );
-                        } else {
-                            println!("  {}", "Cache is fresh".green());
-                        }
-                    }
-                }
+            let index = load_index();
+            println!("  {}: {}", "Directories indexed".bright_white(), index.dirs.len());
+            println!("  {}: {}", "Artifact sizes".bright_white(), index.sizes.len());
+
+            let verified = index
+                .dirs
+                .values()
+                .map(|d| d.verified)
+                .chain(index.sizes.values().map(|s| s.verified));
+
+            if let Some(elapsed) = verified.max().and_then(|newest| newest.elapsed().ok()) {
+                println!(
+                    "  {}: {} ago",
+                    "Last updated".bright_white(),
+                    format_age(elapsed.as_secs())
+                );
             }
+
+            println!(
+                "  {}",
+                "Unchanged directories are reused; changed ones are re-read on the next scan".dimmed()
+            );
         }
         Err(e) => {
             eprintln!("{} {}", "Error reading cache:".red(), e);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_since() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let later = t + Duration::from_secs(60);
+
+        assert!(unchanged_since(t, t, later));
+        assert!(!unchanged_since(later, t, later));
+        // Verified in the same instant the directory changed: can't be trusted
+        assert!(!unchanged_since(t, t, t + Duration::from_millis(500)));
+    }
+}
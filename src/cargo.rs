@@ -0,0 +1,348 @@
+//! # Cargo Module
+//!
+//! Resolves where Cargo projects actually put their build output.
+//!
+//! A `target` directory next to `Cargo.toml` is only the default. Cargo also
+//! honors `CARGO_TARGET_DIR`, `build.target-dir` in `.cargo/config.toml`, and
+//! puts every workspace member's output in the workspace root's target. This
+//! module resolves the real target of each project found during a scan and
+//! groups the projects that share one.
+
+use crate::types::{Category, DeletableItem};
+use crate::utils::has_cargo_marker;
+use globset::Glob;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A resolved target directory and the crates that build into it
+#[derive(Debug, Clone)]
+pub struct CargoTarget {
+    /// Canonical path of the target directory
+    pub target_dir: PathBuf,
+    /// Root of the workspace (or the lone crate) that owns the target
+    pub workspace_root: PathBuf,
+    /// Package names of every crate sharing this target
+    pub members: Vec<String>,
+}
+
+/// Read and parse a TOML file
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Package name from a manifest, falling back to the directory name
+///
+/// Returns `None` for virtual workspace manifests, which aren't crates.
+fn package_name(project_dir: &Path) -> Option<String> {
+    let manifest = read_toml(&project_dir.join("Cargo.toml"))?;
+    let package = manifest.get("package")?;
+
+    Some(
+        package
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| {
+                project_dir
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown")
+                    .to_string()
+            }),
+    )
+}
+
+/// Check if a workspace manifest lists a crate as a member
+fn workspace_includes(workspace: &toml::Table, workspace_root: &Path, project_dir: &Path) -> bool {
+    let Ok(relative) = project_dir.strip_prefix(workspace_root) else {
+        return false;
+    };
+
+    let globs = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|e| e.as_str())
+                    .map(|e| e.trim_end_matches('/').to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let matches = |pattern: &String| {
+        Glob::new(pattern)
+            .map(|glob| glob.compile_matcher().is_match(relative))
+            .unwrap_or(false)
+    };
+
+    globs("members").iter().any(matches) && !globs("exclude").iter().any(matches)
+}
+
+/// Find the workspace root a crate belongs to
+pub fn find_workspace_root(project_dir: &Path) -> PathBuf {
+    // An explicit `package.workspace` key wins
+    if let Some(explicit) = read_toml(&project_dir.join("Cargo.toml")).and_then(|manifest| {
+        manifest
+            .get("package")?
+            .get("workspace")?
+            .as_str()
+            .map(|p| project_dir.join(p))
+    }) {
+        return explicit;
+    }
+
+    for dir in project_dir.ancestors() {
+        let Some(manifest) = read_toml(&dir.join("Cargo.toml")) else {
+            continue;
+        };
+        let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+            continue;
+        };
+
+        if dir == project_dir || workspace_includes(workspace, dir, project_dir) {
+            return dir.to_path_buf();
+        }
+
+        // The nearest workspace doesn't claim this crate, so it stands alone
+        break;
+    }
+
+    project_dir.to_path_buf()
+}
+
+/// `build.target-dir` from the nearest `.cargo/config.toml` (or legacy `.cargo/config`)
+fn configured_target_dir(project_dir: &Path) -> Option<PathBuf> {
+    project_dir.ancestors().find_map(|dir| {
+        ["config.toml", "config"].iter().find_map(|file| {
+            let config = read_toml(&dir.join(".cargo").join(file))?;
+            let target_dir = config.get("build")?.get("target-dir")?.as_str()?;
+            // Relative paths are relative to the directory holding `.cargo`
+            Some(dir.join(target_dir))
+        })
+    })
+}
+
+/// Resolve the target directory a crate builds into
+///
+/// `env_override` is the value of `CARGO_TARGET_DIR` (or
+/// `CARGO_BUILD_TARGET_DIR`), which takes precedence over any config file.
+pub fn resolve_target_dir(project_dir: &Path, env_override: Option<&Path>) -> (PathBuf, PathBuf) {
+    let workspace_root = find_workspace_root(project_dir);
+
+    let target_dir = env_override
+        .map(Path::to_path_buf)
+        .or_else(|| configured_target_dir(project_dir))
+        .unwrap_or_else(|| workspace_root.join("target"));
+
+    (target_dir, workspace_root)
+}
+
+/// Target directory override from the environment
+fn env_target_dir() -> Option<PathBuf> {
+    env::var_os("CARGO_TARGET_DIR")
+        .or_else(|| env::var_os("CARGO_BUILD_TARGET_DIR"))
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Resolve and group the target directories of the given Cargo projects
+///
+/// Only targets that exist on disk and carry one of cargo's markers are
+/// returned, and never one that is or contains `scan_root`, so a stray
+/// `CARGO_TARGET_DIR` can't turn an arbitrary directory into a deletable.
+pub fn resolve_targets(project_dirs: &[PathBuf], scan_root: &Path) -> Vec<CargoTarget> {
+    resolve_targets_with(project_dirs, scan_root, env_target_dir().as_deref())
+}
+
+fn resolve_targets_with(project_dirs: &[PathBuf], scan_root: &Path, env_override: Option<&Path>) -> Vec<CargoTarget> {
+    let mut targets: HashMap<PathBuf, CargoTarget> = HashMap::new();
+    let scan_root = scan_root.canonicalize().unwrap_or_else(|_| scan_root.to_path_buf());
+
+    for project_dir in project_dirs {
+        let (target_dir, workspace_root) = resolve_target_dir(project_dir, env_override);
+        let Ok(target_dir) = target_dir.canonicalize() else {
+            continue;
+        };
+        if scan_root.starts_with(&target_dir) || !has_cargo_marker(&target_dir) {
+            continue;
+        }
+
+        let target = targets.entry(target_dir.clone()).or_insert_with(|| CargoTarget {
+            target_dir,
+            workspace_root,
+            members: Vec::new(),
+        });
+
+        if let Some(name) = package_name(project_dir) {
+            if !target.members.contains(&name) {
+                target.members.push(name);
+            }
+        }
+    }
+
+    let mut targets: Vec<CargoTarget> = targets.into_values().collect();
+    for target in &mut targets {
+        target.members.sort();
+    }
+    targets
+}
+
+/// Merge resolved targets into the walker's pending deletables
+///
+/// Targets the walker missed (outside the tree, custom names) are added, and
+/// anything the walker found inside a target is dropped since the whole
+/// target is already listed.
+pub fn merge_targets(pending: &mut Vec<(PathBuf, Category)>, targets: &[CargoTarget]) {
+    if targets.is_empty() {
+        return;
+    }
+
+    let canonical: Vec<Option<PathBuf>> = pending.iter().map(|(p, _)| p.canonicalize().ok()).collect();
+
+    let mut keep = vec![true; pending.len()];
+    for (idx, path) in canonical.iter().enumerate() {
+        let Some(path) = path else { continue };
+        for target in targets {
+            if path == &target.target_dir {
+                pending[idx].1 = Category::RustTarget;
+            } else if path.starts_with(&target.target_dir) {
+                keep[idx] = false;
+            }
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    pending.retain(|_| keep.next().unwrap_or(true));
+
+    for target in targets {
+        let already_listed = canonical.iter().flatten().any(|p| p == &target.target_dir);
+        if !already_listed {
+            pending.push((target.target_dir.clone(), Category::RustTarget));
+        }
+    }
+}
+
+/// Attribute Rust targets to their workspace and the crates sharing them
+///
+/// Relocated targets don't sit under their workspace, so the workspace root
+/// is recorded on the item for anything that needs to run cargo or rustc there.
+pub fn attribute_targets(items: &mut [DeletableItem], targets: &[CargoTarget]) {
+    if targets.is_empty() {
+        return;
+    }
+
+    for item in items.iter_mut().filter(|i| i.category == Category::RustTarget) {
+        let Ok(path) = item.path.canonicalize() else {
+            continue;
+        };
+
+        if let Some(target) = targets.iter().find(|t| t.target_dir == path) {
+            if let Some(name) = target.workspace_root.file_name().and_then(|n| n.to_str()) {
+                item.project_name = name.to_string();
+            }
+            item.shared_by = target.members.clone();
+            item.workspace = Some(target.workspace_root.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("gigabroom-cargo-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn write_crate(dir: &Path, name: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), format!("[package]\nname = \"{}\"\n", name)).unwrap();
+    }
+
+    /// A directory cargo has built into
+    fn write_target(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(".rustc_info.json"), "{}").unwrap();
+    }
+
+    #[test]
+    fn test_workspace_members_share_root_target() {
+        let root = temp_dir("workspace");
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/standalone\"]\n",
+        )
+        .unwrap();
+        write_crate(&root.join("crates/core"), "ws-core");
+        write_crate(&root.join("crates/cli"), "ws-cli");
+        write_crate(&root.join("crates/standalone"), "standalone");
+        write_target(&root.join("target"));
+        write_target(&root.join("crates/standalone/target"));
+
+        let projects = vec![
+            root.clone(),
+            root.join("crates/core"),
+            root.join("crates/cli"),
+            root.join("crates/standalone"),
+        ];
+        let mut targets = resolve_targets_with(&projects, &root, None);
+        targets.sort_by(|a, b| a.target_dir.cmp(&b.target_dir));
+
+        assert_eq!(targets.len(), 2);
+        let shared = targets.iter().find(|t| t.workspace_root == root).unwrap();
+        assert_eq!(shared.target_dir, root.join("target").canonicalize().unwrap());
+        assert!(shared.members.contains(&"ws-core".to_string()));
+        assert!(shared.members.contains(&"ws-cli".to_string()));
+        assert!(!shared.members.contains(&"standalone".to_string()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_config_target_dir_and_env_override() {
+        let root = temp_dir("config");
+        write_crate(&root.join("app"), "app");
+        fs::create_dir_all(root.join(".cargo")).unwrap();
+        fs::write(root.join(".cargo/config.toml"), "[build]\ntarget-dir = \"out/cargo\"\n").unwrap();
+
+        let (target_dir, workspace_root) = resolve_target_dir(&root.join("app"), None);
+        assert_eq!(target_dir, root.join("out/cargo"));
+        assert_eq!(workspace_root, root.join("app"));
+
+        let shared = root.join("shared-target");
+        let (target_dir, _) = resolve_target_dir(&root.join("app"), Some(&shared));
+        assert_eq!(target_dir, shared);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_env_override_needs_a_real_target() {
+        let root = temp_dir("env");
+        write_crate(&root.join("app"), "app");
+        let projects = vec![root.join("app")];
+
+        // An existing directory cargo never built into isn't a target
+        let important = root.join("important");
+        fs::create_dir_all(&important).unwrap();
+        assert!(resolve_targets_with(&projects, &root, Some(&important)).is_empty());
+
+        write_target(&important);
+        assert_eq!(resolve_targets_with(&projects, &root, Some(&important)).len(), 1);
+
+        // Nor is the scan root, or anything above it
+        write_target(&root);
+        assert!(resolve_targets_with(&projects, &root, Some(&root)).is_empty());
+        assert!(resolve_targets_with(&projects, &root.join("app"), Some(&root)).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,18 +1,31 @@
-use crate::types::{Category, DeletableItem};
+use crate::cargo::find_workspace_root;
+use crate::disk;
+use crate::inuse::InUseSnapshot;
+use crate::journal::{self, Action, JournalEntry};
+use crate::quarantine::Quarantine;
+use crate::remove::{remove_path, FailureKind, RemoveFailure};
+use crate::trash::{self, move_to_trash, TrashedItem};
+use crate::trim::{trim_rust_target, RustcVersions, TrimReport};
+use crate::types::{Category, DeletableItem, DeleteMode};
+use crate::ui::SafetyLevel;
+use crate::validate::revalidate_selection;
 use crate::utils::format_size;
 use crate::{print_error, println_unless_quiet};
 use colored::*;
-use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect, Select};
-use std::collections::{HashMap, HashSet};
-
-#[cfg(target_os = "macos")]
-use std::process::Command;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
 /// Select categories interactively
 pub fn select_categories() -> Vec<Category> {
     crate::ui::show_breadcrumb(&["Scan & Clean", "Select Categories"]);
 
-    let categories = vec![
+    let mut categories = vec![
         ("🦀 Rust - target directories", Category::RustTarget),
         ("📦 JavaScript/Node - node_modules", Category::NodeModules),
         ("🐍 Python - cache & venv", Category::PythonCache),
@@ -29,9 +42,23 @@ pub fn select_categories() -> Vec<Category> {
         ("📝 Temp/Logs - *.log/*.tmp", Category::TempFiles),
         ("⚠️  Package Caches (DANGEROUS: global caches!)", Category::PackageCache),
         ("📁 General - build/dist/out", Category::BuildCache),
-    ];
+        ("🏷️  Tagged caches - dirs with CACHEDIR.TAG", Category::TaggedCache),
+    ]
+    .into_iter()
+    .map(|(name, cat)| (name.to_string(), cat))
+    .collect::<Vec<_>>();
+
+    // User-defined rules from the rules file
+    for cat in crate::rules::custom_categories() {
+        let name = if cat.is_dangerous() {
+            format!("⚠️  {} (custom rule, DANGEROUS)", cat.name())
+        } else {
+            format!("🔧 {} (custom rule)", cat.name())
+        };
+        categories.push((name, cat));
+    }
 
-    let category_names: Vec<String> = categories.iter().map(|(name, _)| (*name).to_string()).collect();
+    let category_names: Vec<String> = categories.iter().map(|(name, _)| name.clone()).collect();
 
     println!("\n{}", "Select categories to clean:".bright_cyan().bold());
     crate::ui::show_inline_hint();
@@ -77,6 +104,9 @@ pub fn select_categories() -> Vec<Category> {
                     println!("    Includes: npm, pip, yarn, Maven global caches");
                     println!("    All projects will need to re-download dependencies!");
                 }
+                Category::Custom(custom) => {
+                    println!("  {} {} is marked dangerous in your rules file", "•".bright_red(), custom.label);
+                }
                 _ => {}
             }
         }
@@ -172,11 +202,18 @@ pub fn show_interactive_menu(items: &[DeletableItem]) -> Vec<usize> {
         .iter()
         .map(|&idx| {
             let item = &items[idx];
+            let shared = if item.shared_by.len() > 1 {
+                format!(" [shared by {} crates]", item.shared_by.len())
+            } else {
+                String::new()
+            };
             format!(
-                "{} - {} ({})",
+                "{} - {} ({}){}{}",
                 item.project_name,
                 item.category.name(),
-                format_size(item.size)
+                format_size(item.size),
+                if item.git.is_ignored() { " [git-ignored]" } else { "" },
+                shared
             )
         })
         .collect();
@@ -222,6 +259,8 @@ fn get_category_emoji(cat: &Category) -> &'static str {
         Category::TempFiles => "📝",
         Category::PackageCache => "⚠️",
         Category::BuildCache => "📁",
+        Category::TaggedCache => "🏷️",
+        Category::Custom(_) => "🔧",
     }
 }
 
@@ -230,7 +269,7 @@ fn select_items_by_category(items: &[DeletableItem]) -> Vec<usize> {
     // Group items by category
     let mut category_map: HashMap<Category, Vec<usize>> = HashMap::new();
     for (idx, item) in items.iter().enumerate() {
-        category_map.entry(item.category).or_insert_with(Vec::new).push(idx);
+        category_map.entry(item.category).or_default().push(idx);
     }
 
     // Calculate size per category
@@ -370,9 +409,10 @@ fn select_items_in_category(
         .map(|&idx| {
             let item = &items[idx];
             format!(
-                "{} ({})",
+                "{} ({}){}",
                 item.project_name,
-                format_size(item.size)
+                format_size(item.size),
+                if item.git.is_ignored() { " [git-ignored]" } else { "" }
             )
         })
         .collect();
@@ -410,11 +450,62 @@ fn select_items_in_category(
         .collect()
 }
 
+/// Remove items that contain git-tracked files, reporting what was skipped
+pub fn drop_protected(items: &mut Vec<DeletableItem>, quiet: bool) {
+    let before = items.len();
+    items.retain(|item| !item.git.is_protected());
+    let skipped = before - items.len();
+
+    if skipped > 0 {
+        println_unless_quiet!(
+            quiet,
+            "{} {} items contain files tracked by git, or git couldn't check them {}",
+            "🔒 Protected:".bright_yellow().bold(),
+            skipped,
+            "(use --include-tracked to delete them anyway)".dimmed()
+        );
+    }
+}
+
+/// Remove items a project's `.gigabroom.toml` keeps, reporting what was skipped
+pub fn drop_kept(items: &mut Vec<DeletableItem>, quiet: bool) {
+    let before = items.len();
+    items.retain(|item| item.kept.is_none());
+    let skipped = before - items.len();
+
+    if skipped > 0 {
+        println_unless_quiet!(
+            quiet,
+            "{} {} items are marked keep in a .gigabroom.toml {}",
+            "🔒 Kept:".bright_yellow().bold(),
+            skipped,
+            "(`gigabroom unkeep <path>` to release them)".dimmed()
+        );
+    }
+}
+
+/// Bytes freed (and the trash entry, when trashing), or why an item failed
+type ItemOutcome = Result<(u64, Option<TrashedItem>), RemoveFailure>;
+
 /// Delete items with optional dry-run mode
+///
+/// Items are processed in parallel behind a progress bar counting bytes and
+/// items; every item's outcome is still reported and journaled.
+///
+/// With [`DeleteMode::Trash`] items are moved to the trash and logged so
+/// `gigabroom restore` can bring them back; with [`DeleteMode::Quarantine`]
+/// they are renamed into a quarantine run until `gigabroom purge`.
+///
+/// Every item is re-checked against `roots` (the scanned directories) first, and
+/// skipped if it changed since the scan or, unless `force_in_use`, if a
+/// running process or build is using it.
 pub fn delete_items(
     items: &[DeletableItem],
     indices: &[usize],
+    roots: &[PathBuf],
+    mode: DeleteMode,
     dry_run: bool,
+    force_in_use: bool,
     quiet: bool,
 ) -> bool {
     if indices.is_empty() {
@@ -422,6 +513,20 @@ pub fn delete_items(
         return false;
     }
 
+    let (indices, changed) = revalidate_selection(items, indices, roots);
+    if !changed.is_empty() {
+        let changed: Vec<(usize, String)> = changed.into_iter().map(|(idx, m)| (idx, m.to_string())).collect();
+        let heading = format!("Skipping {} items that changed since the scan:", changed.len());
+        report_skipped(items, &changed, &heading, mode.into(), dry_run, quiet);
+    }
+
+    let indices = skip_in_use(items, indices, force_in_use, mode.into(), dry_run, quiet);
+    if indices.is_empty() {
+        println_unless_quiet!(quiet, "\n{}", "Nothing left to delete.".yellow());
+        return false;
+    }
+    let indices = indices.as_slice();
+
     if dry_run {
         println_unless_quiet!(
             quiet,
@@ -431,9 +536,15 @@ pub fn delete_items(
         println_unless_quiet!(quiet, "{}", "=".repeat(80).bright_black());
 
         let mut total_size = 0u64;
+        let mut entries = Vec::new();
         for &idx in indices {
             if let Some(item) = items.get(idx) {
-                println_unless_quiet!(quiet, "Would delete: {}", item.path.display());
+                entries.push(JournalEntry::new(item, mode.into(), true, None));
+                match mode {
+                    DeleteMode::Delete => println_unless_quiet!(quiet, "Would delete: {}", item.path.display()),
+                    DeleteMode::Trash => println_unless_quiet!(quiet, "Would move to trash: {}", item.path.display()),
+                    DeleteMode::Quarantine => println_unless_quiet!(quiet, "Would quarantine: {}", item.path.display()),
+                }
                 total_size += item.size;
             }
         }
@@ -442,7 +553,13 @@ pub fn delete_items(
         println_unless_quiet!(
             quiet,
             "{} {} items",
-            "Would delete:".bright_yellow().bold(),
+            match mode {
+                DeleteMode::Delete => "Would delete:",
+                DeleteMode::Trash => "Would trash:",
+                DeleteMode::Quarantine => "Would quarantine:",
+            }
+            .bright_yellow()
+            .bold(),
             indices.len().to_string().bright_yellow().bold()
         );
         println_unless_quiet!(
@@ -452,92 +569,467 @@ pub fn delete_items(
             format_size(total_size).bright_yellow().bold()
         );
 
+        if let Err(e) = journal::record(&entries) {
+            print_error!("Could not write to the journal: {}", e);
+        }
+
         return false;
     }
 
     println_unless_quiet!(
         quiet,
         "\n{}",
-        "Deleting selected items...".bright_yellow().bold()
+        match mode {
+            DeleteMode::Delete => "Deleting selected items...",
+            DeleteMode::Trash => "Moving selected items to trash...",
+            DeleteMode::Quarantine => "Quarantining selected items...",
+        }
+        .bright_yellow()
+        .bold()
     );
 
-    let mut deleted_count = 0;
-    let mut failed_count = 0;
-    let mut total_freed = 0u64;
+    // Trash and quarantine are renames that free nothing yet
+    let space_before = if mode == DeleteMode::Delete && !quiet {
+        disk::by_mount(indices.iter().filter_map(|&idx| items.get(idx)))
+    } else {
+        Vec::new()
+    };
+
+    let total_size: u64 = indices.iter().filter_map(|&idx| items.get(idx)).map(|item| item.size).sum();
+    let pb = if quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(total_size)
+    };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} {prefix} [{bar:40.cyan/blue}] {bytes}/{total_bytes} {msg} ({binary_bytes_per_sec}, ETA {eta})")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_prefix(mode.verb());
+    pb.set_message(format!("0/{} items", indices.len()));
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let quarantine = Mutex::new(Quarantine::new());
+    let finished = AtomicUsize::new(0);
+
+    // Items are removed in parallel; real deletions report bytes as each file goes,
+    // trash and quarantine are renames and settle the bar in one step
+    let results: Vec<(&DeletableItem, ItemOutcome)> = indices
+        .par_iter()
+        .filter_map(|&idx| items.get(idx))
+        .map(|item| {
+            let counted = AtomicU64::new(0);
+            let result = match mode {
+                DeleteMode::Delete => remove_path(&item.path, &|bytes| {
+                    counted.fetch_add(bytes, Ordering::Relaxed);
+                    pb.inc(bytes);
+                })
+                .map(|freed| (freed, None)),
+                DeleteMode::Trash => move_to_trash(item)
+                    .map(|entry| (item.size, Some(entry)))
+                    .map_err(|e| RemoveFailure::whole(&item.path, item.size, e)),
+                DeleteMode::Quarantine => quarantine
+                    .lock()
+                    .unwrap()
+                    .add(item)
+                    .map(|_| (item.size, None))
+                    .map_err(|e| RemoveFailure::whole(&item.path, item.size, e)),
+            };
+
+            // The bar tracks bytes processed, so account for whatever the walk didn't see
+            pb.inc(item.size.saturating_sub(counted.load(Ordering::Relaxed)));
+            let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+            pb.set_message(format!("{}/{} items", done, indices.len()));
 
-    for &idx in indices {
-        if let Some(item) = items.get(idx) {
             if !quiet {
-                print!("Deleting {} ... ", item.path.display());
+                pb.suspend(|| match &result {
+                    Ok(_) => println!("{} {}", "✓".green().bold(), item.path.display()),
+                    Err(failure) => println!(
+                        "{} {} {}{}",
+                        "✗".red().bold(),
+                        item.path.display(),
+                        failure.message.red(),
+                        partial_note(failure).dimmed()
+                    ),
+                });
             }
 
-            match std::fs::remove_dir_all(&item.path)
-                .or_else(|_| std::fs::remove_file(&item.path))
-            {
-                Ok(_) => {
-                    println_unless_quiet!(quiet, "{}", "✓".green().bold());
-                    deleted_count += 1;
-                    total_freed += item.size;
-                }
-                Err(e) => {
-                    if quiet {
-                        print_error!("Failed to delete {}: {}", item.path.display(), e);
-                    } else {
-                        println!("{} {}", "✗".red().bold(), e.to_string().red());
-                    }
-                    failed_count += 1;
+            (item, result)
+        })
+        .collect();
+
+    pb.finish_and_clear();
+
+    let mut deleted_count = 0;
+    let mut total_freed = 0u64;
+    let mut trashed = Vec::new();
+    let mut entries = Vec::new();
+    let mut failures: Vec<(&DeletableItem, RemoveFailure)> = Vec::new();
+
+    for (item, result) in results {
+        let mut entry = JournalEntry::new(item, mode.into(), false, result.as_ref().err().map(|f| f.message.clone()));
+
+        match result {
+            Ok((freed, trashed_item)) => {
+                trashed.extend(trashed_item);
+                deleted_count += 1;
+                total_freed += freed;
+                entry.size = freed;
+            }
+            Err(failure) => {
+                if quiet {
+                    print_error!("Failed to delete {}: {}{}", item.path.display(), failure, partial_note(&failure));
                 }
+                total_freed += failure.freed;
+                entry.size = failure.freed;
+                failures.push((item, failure));
             }
         }
+        entries.push(entry);
+    }
+
+    if let Err(e) = trash::record(&trashed) {
+        print_error!("Could not update the trash log, `gigabroom restore` won't list these items: {}", e);
+    }
+    if let Err(e) = journal::record(&entries) {
+        print_error!("Could not write to the journal: {}", e);
     }
 
     println_unless_quiet!(quiet, "\n{}", "=".repeat(80).bright_black());
     println_unless_quiet!(
         quiet,
         "{} {} items",
-        "Successfully deleted:".bright_green().bold(),
+        match mode {
+            DeleteMode::Delete => "Successfully deleted:",
+            DeleteMode::Trash => "Moved to trash:",
+            DeleteMode::Quarantine => "Quarantined:",
+        }
+        .bright_green()
+        .bold(),
         deleted_count.to_string().bright_green().bold()
     );
 
-    if failed_count > 0 {
+    if !failures.is_empty() && !quiet {
+        show_failures(&failures);
+    }
+
+    match mode {
+        DeleteMode::Delete => println_unless_quiet!(
+            quiet,
+            "{} {}",
+            "Space freed:".bright_green().bold(),
+            format_size(total_freed).bright_green().bold()
+        ),
+        DeleteMode::Trash => println_unless_quiet!(
+            quiet,
+            "{} {} {}",
+            "Space held in trash:".bright_green().bold(),
+            format_size(total_freed).bright_green().bold(),
+            "(empty the trash to free it, `gigabroom restore` to undo)".dimmed()
+        ),
+        DeleteMode::Quarantine => println_unless_quiet!(
+            quiet,
+            "{} {} {}",
+            "Space held in quarantine:".bright_green().bold(),
+            format_size(total_freed).bright_green().bold(),
+            "(`gigabroom purge` frees it, `gigabroom quarantine restore` undoes)".dimmed()
+        ),
+    }
+
+    disk::show_space_change(&space_before);
+
+    deleted_count > 0
+}
+
+/// Report and journal items that were skipped, with the reason for each
+fn report_skipped(
+    items: &[DeletableItem],
+    skipped: &[(usize, String)],
+    heading: &str,
+    action: Action,
+    dry_run: bool,
+    quiet: bool,
+) {
+    println_unless_quiet!(quiet, "{}", heading.yellow());
+
+    let mut entries = Vec::new();
+    for (idx, reason) in skipped {
+        let Some(item) = items.get(*idx) else { continue };
+        println_unless_quiet!(quiet, "    {} {}", item.path.display(), format!("({})", reason).dimmed());
+
+        let mut entry = JournalEntry::new(item, action, dry_run, Some(format!("skipped: {}", reason)));
+        entry.size = 0;
+        entries.push(entry);
+    }
+
+    if let Err(e) = journal::record(&entries) {
+        print_error!("Could not write to the journal: {}", e);
+    }
+}
+
+/// Drop items a running process or build is using right now, unless forced
+///
+/// Uses a fresh snapshot rather than what the scan saw, since builds start
+/// and stop while menus sit open.
+fn skip_in_use(
+    items: &[DeletableItem],
+    indices: Vec<usize>,
+    force: bool,
+    action: Action,
+    dry_run: bool,
+    quiet: bool,
+) -> Vec<usize> {
+    let snapshot = InUseSnapshot::capture();
+    let reasons: Vec<Option<String>> = indices
+        .par_iter()
+        .map(|&idx| items.get(idx).and_then(|item| snapshot.reason(item)))
+        .collect();
+
+    let in_use: Vec<(usize, String)> = indices
+        .iter()
+        .zip(&reasons)
+        .filter_map(|(&idx, reason)| reason.clone().map(|r| (idx, r)))
+        .collect();
+
+    if in_use.is_empty() {
+        return indices;
+    }
+
+    if force {
         println_unless_quiet!(
             quiet,
-            "{} {} items",
-            "Failed to delete:".bright_red().bold(),
-            failed_count.to_string().bright_red().bold()
+            "{}",
+            format!("⚠ {} items are in use; removing them anyway (--force-in-use)", in_use.len()).bright_yellow()
+        );
+        return indices;
+    }
+
+    report_skipped(
+        items,
+        &in_use,
+        &format!("Skipping {} items in use (--force-in-use removes them anyway):", in_use.len()),
+        action,
+        dry_run,
+        quiet,
+    );
+
+    indices
+        .into_iter()
+        .zip(reasons)
+        .filter(|(_, reason)| reason.is_none())
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// " (freed X, Y left)" for items that were only partly removed
+fn partial_note(failure: &RemoveFailure) -> String {
+    if failure.is_partial() {
+        format!(" (freed {}, {} left)", format_size(failure.freed), format_size(failure.left))
+    } else {
+        String::new()
+    }
+}
+
+/// Summarize failed items, grouped by cause
+fn show_failures(failures: &[(&DeletableItem, RemoveFailure)]) {
+    println!(
+        "{} {} items",
+        "Failed to delete:".bright_red().bold(),
+        failures.len().to_string().bright_red().bold()
+    );
+
+    let mut groups: BTreeMap<FailureKind, Vec<&(&DeletableItem, RemoveFailure)>> = BTreeMap::new();
+    for failure in failures {
+        groups.entry(failure.1.kind).or_default().push(failure);
+    }
+
+    for (kind, group) in &groups {
+        let left: u64 = group.iter().map(|(_, f)| f.left).sum();
+        println!(
+            "  {} {} {}",
+            format!("{}:", kind.label()).bright_red(),
+            group.len(),
+            format!("({} left)", format_size(left)).dimmed()
         );
+        for (item, failure) in group {
+            println!("    {}{}", item.path.display(), partial_note(failure).dimmed());
+        }
+    }
+
+    let partial: Vec<&RemoveFailure> = failures.iter().map(|(_, f)| f).filter(|f| f.is_partial()).collect();
+    if !partial.is_empty() {
+        println!(
+            "{} {} items {}",
+            "Partially deleted:".bright_yellow().bold(),
+            partial.len().to_string().bright_yellow().bold(),
+            format!(
+                "(freed {}, {} left behind)",
+                format_size(partial.iter().map(|f| f.freed).sum()),
+                format_size(partial.iter().map(|f| f.left).sum())
+            )
+            .dimmed()
+        );
+    }
+}
+
+/// Trim Rust targets in place, keeping what the current toolchain still uses
+///
+/// Targets that changed since the scan are skipped, as are targets a cargo
+/// build is using unless `force_in_use`. Returns true if anything was trimmed.
+pub fn trim_items(
+    items: &[DeletableItem],
+    indices: &[usize],
+    roots: &[PathBuf],
+    max_age: Duration,
+    dry_run: bool,
+    force_in_use: bool,
+    quiet: bool,
+) -> bool {
+    let (indices, changed) = revalidate_selection(items, indices, roots);
+    if !changed.is_empty() {
+        let changed: Vec<(usize, String)> = changed.into_iter().map(|(idx, m)| (idx, m.to_string())).collect();
+        let heading = format!("Skipping {} items that changed since the scan:", changed.len());
+        report_skipped(items, &changed, &heading, Action::Trim, dry_run, quiet);
+    }
+
+    let indices = skip_in_use(items, indices, force_in_use, Action::Trim, dry_run, quiet);
+    if indices.is_empty() {
+        return false;
     }
 
     println_unless_quiet!(
         quiet,
-        "{} {}",
-        "Space freed:".bright_green().bold(),
-        format_size(total_freed).bright_green().bold()
+        "\n{}",
+        if dry_run {
+            "DRY RUN - Trimming Rust targets (nothing will be removed)"
+        } else {
+            "Trimming Rust targets..."
+        }
+        .bright_yellow()
+        .bold()
     );
 
-    deleted_count > 0
+    // Each workspace may pin its own toolchain
+    let mut rustc_versions = RustcVersions::default();
+    let mut warned_no_rustc = false;
+
+    let mut total = TrimReport::default();
+    let mut entries = Vec::new();
+
+    for &idx in &indices {
+        if let Some(item) = items.get(idx) {
+            // A relocated target's parent says nothing about its toolchain
+            let root = item
+                .workspace
+                .clone()
+                .unwrap_or_else(|| find_workspace_root(item.path.parent().unwrap_or(&item.path)));
+            let rustc_version = rustc_versions.for_root(&root);
+            if rustc_version.is_none() && !warned_no_rustc {
+                warned_no_rustc = true;
+                println_unless_quiet!(
+                    quiet,
+                    "{}",
+                    "rustc not found - only trimming by age, not by toolchain".dimmed()
+                );
+            }
+
+            let report = trim_rust_target(&item.path, max_age, rustc_version, dry_run);
+
+            let error = (!report.errors.is_empty()).then(|| report.errors.join("; "));
+            let mut entry = JournalEntry::new(item, Action::Trim, dry_run, error);
+            entry.size = report.trimmed_bytes;
+            entries.push(entry);
+
+            println_unless_quiet!(
+                quiet,
+                "{} {} {}",
+                if dry_run { "Would trim" } else { "Trimmed" },
+                item.path.display(),
+                format!(
+                    "- {} ({} old units, {} from other toolchains)",
+                    format_size(report.trimmed_bytes),
+                    report.stale_age_units,
+                    report.stale_toolchain_units
+                )
+                .dimmed()
+            );
+
+            for error in &report.errors {
+                print_error!("Failed to trim {}", error);
+            }
+
+            total.trimmed_bytes += report.trimmed_bytes;
+            total.removed_entries += report.removed_entries;
+            total.errors.extend(report.errors);
+        }
+    }
+
+    println_unless_quiet!(
+        quiet,
+        "{} {} {}",
+        if dry_run { "Would trim:" } else { "Space trimmed:" }.bright_green().bold(),
+        format_size(total.trimmed_bytes).bright_green().bold(),
+        format!("({} entries)", total.removed_entries).dimmed()
+    );
+
+    if let Err(e) = journal::record(&entries) {
+        print_error!("Could not write to the journal: {}", e);
+    }
+
+    !dry_run && total.removed_entries > 0
 }
 
 /// Confirm deletion with user - Enhanced visual summary
-pub fn confirm_deletion(item_count: usize, total_size: u64) -> bool {
-    show_deletion_summary(item_count, total_size, &HashMap::new());
+///
+/// Defaults to no. Permanently deleting dangerous items takes typing "delete".
+pub fn confirm_deletion(item_count: usize, total_size: u64, mode: DeleteMode, safety: SafetyLevel) -> bool {
+    show_deletion_summary(item_count, total_size, &HashMap::new(), mode);
+
+    if safety == SafetyLevel::Dangerous && !mode.is_undoable() {
+        println!(
+            "\n{} {}",
+            "⚠".red().bold(),
+            "This includes items that are expensive or impossible to get back.".red()
+        );
+        return Input::<String>::new()
+            .with_prompt("Type 'delete' to proceed")
+            .allow_empty(true)
+            .interact_text()
+            .is_ok_and(|answer| answer.trim() == "delete");
+    }
 
     Confirm::new()
-        .with_prompt("Proceed with deletion?")
+        .with_prompt(if mode.is_undoable() {
+            "Proceed?"
+        } else {
+            "Proceed with deletion?"
+        })
         .default(false)
         .interact()
         .unwrap_or(false)
 }
 
 /// Show detailed deletion summary with visual box
-pub fn show_deletion_summary(item_count: usize, total_size: u64, categories: &HashMap<Category, usize>) {
+pub fn show_deletion_summary(
+    item_count: usize,
+    total_size: u64,
+    categories: &HashMap<Category, usize>,
+    mode: DeleteMode,
+) {
     use crate::ui;
 
     println!();
 
     // Build content lines
     let mut content = vec![];
-    content.push(format!("{}  {} items", "Will Delete:".bright_white().bold(), item_count.to_string().bright_yellow().bold()));
+    let action = match mode {
+        DeleteMode::Delete => "Will Delete:",
+        DeleteMode::Trash => "Will Trash:",
+        DeleteMode::Quarantine => "Will Quarantine:",
+    };
+    content.push(format!("{}  {} items", action.bright_white().bold(), item_count.to_string().bright_yellow().bold()));
     content.push(format!("{}  {}", "Will Free:".bright_white().bold(), format_size(total_size).bright_green().bold()));
 
     if !categories.is_empty() {
@@ -564,13 +1056,21 @@ pub fn show_deletion_summary(item_count: usize, total_size: u64, categories: &Ha
                 Category::TempFiles => "📝",
                 Category::PackageCache => "⚠️ ",
                 Category::BuildCache => "📁",
+                Category::TaggedCache => "🏷️",
+                Category::Custom(_) => "🔧",
             };
             content.push(format!("  {} {:20} {} items", emoji, cat.name(), count));
         }
     }
 
     content.push(String::new());
-    content.push("⚠ Warning: This action cannot be undone!".bright_red().to_string());
+    match mode {
+        DeleteMode::Delete => content.push("⚠ Warning: This action cannot be undone!".bright_red().to_string()),
+        DeleteMode::Trash => content.push("↩ Undo with: gigabroom restore".bright_green().to_string()),
+        DeleteMode::Quarantine => {
+            content.push("↩ Undo with: gigabroom quarantine restore".bright_green().to_string())
+        }
+    }
 
     content.push(String::new());
     content.push(format!("{} {} {} {}",
@@ -603,7 +1103,7 @@ pub fn show_statistics(items: &[DeletableItem]) {
 
     // Sort categories by size (descending)
     let mut sorted_categories: Vec<_> = category_stats.iter().collect();
-    sorted_categories.sort_by(|a, b| b.1.0.cmp(&a.1.0));
+    sorted_categories.sort_by_key(|(_, (size, _))| std::cmp::Reverse(*size));
 
     // Display statistics
     println!("\n{}", "═".repeat(80).bright_cyan());
@@ -642,6 +1142,8 @@ pub fn show_statistics(items: &[DeletableItem]) {
             Category::TempFiles => "📝",
             Category::PackageCache => "⚠️ ",
             Category::BuildCache => "📁",
+            Category::TaggedCache => "🏷️",
+            Category::Custom(_) => "🔧",
         };
 
         println!(
@@ -657,7 +1159,7 @@ pub fn show_statistics(items: &[DeletableItem]) {
 
     // Find largest projects
     let mut sorted_items = items.to_vec();
-    sorted_items.sort_by(|a, b| b.size.cmp(&a.size));
+    sorted_items.sort_by_key(|item| std::cmp::Reverse(item.size));
 
     println!("\n{}", "Largest Items:".bright_white().bold());
     println!("{}", "-".repeat(80).bright_black());
@@ -674,61 +1176,3 @@ pub fn show_statistics(items: &[DeletableItem]) {
 
     println!("{}", "═".repeat(80).bright_cyan());
 }
-
-/// Display disk space context (macOS only for now)
-#[allow(dead_code)]
-#[cfg(target_os = "macos")]
-pub fn show_disk_space(reclaimable_size: u64) {
-    // Get disk space info using `df -h /`
-    let output = Command::new("df")
-        .args(&["-k", "/"])  // Use kilobytes for consistent parsing
-        .output();
-
-    if let Ok(output) = output {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        // Parse df output: Filesystem 1024-blocks Used Available Capacity Mounted
-        if let Some(line) = output_str.lines().nth(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                if let (Ok(total_kb), Ok(used_kb), Ok(avail_kb)) = (
-                    parts[1].parse::<u64>(),
-                    parts[2].parse::<u64>(),
-                    parts[3].parse::<u64>(),
-                ) {
-                    let total = total_kb * 1024;
-                    let used = used_kb * 1024;
-                    let available = avail_kb * 1024;
-                    let used_percent = (used as f64 / total as f64) * 100.0;
-                    let after_cleanup = available + reclaimable_size;
-                    let after_percent = ((total - used + reclaimable_size) as f64 / total as f64) * 100.0;
-
-                    println!("\n{}", "═".repeat(80).bright_blue());
-                    println!("  {}", "💾 DISK SPACE CONTEXT".bright_blue().bold());
-                    println!("{}", "═".repeat(80).bright_blue());
-
-                    println!("\n{}", "Current:".bright_white().bold());
-                    println!("  Total:      {}", format_size(total).bright_white());
-                    println!("  Used:       {} ({:.1}%)", format_size(used).bright_red(), used_percent);
-                    println!("  Available:  {}", format_size(available).bright_green());
-
-                    println!("\n{}", "After cleanup:".bright_white().bold());
-                    println!("  Available:  {} ({:.1}% free)",
-                        format_size(after_cleanup).bright_green().bold(),
-                        after_percent
-                    );
-                    println!("  Gain:       {} ({:.1}%)",
-                        format_size(reclaimable_size).bright_cyan().bold(),
-                        (reclaimable_size as f64 / total as f64) * 100.0
-                    );
-
-                    println!("{}", "═".repeat(80).bright_blue());
-                }
-            }
-        }
-    }
-}
-
-#[cfg(not(target_os = "macos"))]
-pub fn show_disk_space(_reclaimable_size: u64) {
-    // Not implemented for non-macOS systems yet
-}
@@ -1,5 +1,5 @@
-use crate::types::Category;
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::types::{AgeSource, Category, FreePolicy};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 /// Gigabroom 🧹 - Sweep away gigabytes of build artifacts
 #[derive(Parser, Debug)]
@@ -10,9 +10,23 @@ use clap::{Parser, Subcommand, ValueEnum};
     gigabroom                                  # Launch interactive menu\n  \
     gigabroom scan                             # Scan current directory\n  \
     gigabroom scan ~/projects -d 5             # Scan with max depth 5\n  \
+    gigabroom scan ~ --exclude Steam           # Never walk into Steam directories\n  \
     gigabroom clean --category rust node       # Clean Rust and Node artifacts\n  \
     gigabroom clean --all --yes                # Clean everything without confirmation\n  \
     gigabroom clean --dry-run                  # Preview what would be deleted\n  \
+    gigabroom clean --older-than 30d           # Only items untouched for a month\n  \
+    gigabroom clean --category rust --trim     # Prune stale data inside Rust targets\n  \
+    gigabroom clean --preset quick             # Safe, common build artifacts\n  \
+    gigabroom clean --free 40GB                # Free 40 GB, safest and oldest/biggest first\n  \
+    gigabroom clean --policy --dry-run         # What the retention rules allow, and why\n  \
+    gigabroom clean --trash                    # Move to trash instead of deleting\n  \
+    gigabroom restore                          # Bring back trashed items\n  \
+    gigabroom clean --all --yes --quarantine   # Keep an undo window on build boxes\n  \
+    gigabroom purge --older-than 7d            # Delete quarantine runs older than a week\n  \
+    gigabroom history --totals month           # Space reclaimed per category per month\n  \
+    gigabroom watchdog ~ --min-free 15%        # Clean when less than 15% is free\n  \
+    gigabroom schedule install ~ --daily       # Clean every day with the quick preset\n  \
+    gigabroom keep ./build                     # Never offer this project's build/\n  \
     gigabroom cache clear                      # Clear the scan cache")]
 pub struct Cli {
     #[command(subcommand)]
@@ -31,29 +45,23 @@ pub struct Cli {
 pub enum Commands {
     /// Scan a directory for deletable items
     Scan {
-        /// Directory to scan (defaults to current directory)
-        #[arg(default_value = ".")]
-        path: String,
+        /// Directories to scan (defaults to the configured roots, or the current directory)
+        paths: Vec<String>,
 
-        /// Maximum depth to scan
-        #[arg(short = 'd', long, default_value = "10")]
-        max_depth: usize,
+        /// Maximum depth to scan (default 10, or scan.max_depth from the config)
+        #[arg(short = 'd', long)]
+        max_depth: Option<usize>,
 
         /// Force fresh scan, ignore cache
         #[arg(short, long)]
         force: bool,
 
-        /// Use system indexing (Spotlight on macOS) - faster but may miss items
+        /// Use system indexing (Spotlight on macOS, locate on Linux) - faster but may miss items
         #[arg(short, long)]
         index: bool,
 
-        /// Minimum size threshold (e.g., "100MB", "1GB")
-        #[arg(short = 's', long)]
-        min_size: Option<String>,
-
-        /// Only show items older than (e.g., "30d", "1w", "7d")
-        #[arg(short = 'o', long)]
-        older_than: Option<String>,
+        #[command(flatten)]
+        filters: FilterArgs,
 
         /// Output results as JSON
         #[arg(short, long)]
@@ -62,13 +70,12 @@ pub enum Commands {
 
     /// Clean (delete) build artifacts and caches
     Clean {
-        /// Directory to clean (defaults to current directory)
-        #[arg(default_value = ".")]
-        path: String,
+        /// Directories to clean (defaults to the configured roots, or the current directory)
+        paths: Vec<String>,
 
-        /// Maximum depth to scan
-        #[arg(short = 'd', long, default_value = "10")]
-        max_depth: usize,
+        /// Maximum depth to scan (default 10, or scan.max_depth from the config)
+        #[arg(short = 'd', long)]
+        max_depth: Option<usize>,
 
         /// Language/category to clean: rust, node, python, java-maven, java-gradle, build, git, cargo
         #[arg(short, long, value_enum)]
@@ -78,6 +85,14 @@ pub enum Commands {
         #[arg(short, long)]
         all: bool,
 
+        /// Clean with a preset: quick, deep, nuclear, or one from the config file
+        #[arg(short, long, value_name = "NAME", conflicts_with_all = ["category", "all"])]
+        preset: Option<String>,
+
+        /// Clean what the retention rules in the config file allow, per category
+        #[arg(long, conflicts_with_all = ["all", "preset", "free"])]
+        policy: bool,
+
         /// Skip confirmation prompts
         #[arg(short = 'y', long)]
         yes: bool,
@@ -86,6 +101,39 @@ pub enum Commands {
         #[arg(short = 'n', long)]
         dry_run: bool,
 
+        /// Also delete items that contain files tracked by git
+        #[arg(long)]
+        include_tracked: bool,
+
+        /// Also delete items that running processes or builds are using
+        #[arg(long)]
+        force_in_use: bool,
+
+        /// Pick items until this much space is freed (e.g., "40GB"), safest categories first
+        #[arg(long, value_name = "SIZE", conflicts_with = "trim")]
+        free: Option<String>,
+
+        /// How --free orders items within a safety level
+        #[arg(long, value_enum, default_value = "score", requires = "free")]
+        order: FreePolicy,
+
+        /// Let --free pick dangerous categories (global package caches, dangerous custom rules)
+        #[arg(long, requires = "free")]
+        allow_dangerous: bool,
+
+        /// Trim Rust targets instead of deleting them: drop build data unused for AGE
+        /// (default 7d) and artifacts from other toolchains
+        #[arg(long, value_name = "AGE", num_args = 0..=1, default_missing_value = "7d")]
+        trim: Option<String>,
+
+        /// Move items to the trash instead of deleting them (undo with `gigabroom restore`)
+        #[arg(long)]
+        trash: bool,
+
+        /// Move items into .gigabroom-quarantine/ until `gigabroom purge` deletes them
+        #[arg(long, conflicts_with = "trash")]
+        quarantine: bool,
+
         /// Force fresh scan, ignore cache
         #[arg(short, long)]
         force: bool,
@@ -94,19 +142,113 @@ pub enum Commands {
         #[arg(short, long)]
         index: bool,
 
-        /// Minimum size threshold (e.g., "100MB", "1GB")
-        #[arg(short = 's', long)]
-        min_size: Option<String>,
-
-        /// Only show items older than (e.g., "30d", "1w", "7d")
-        #[arg(short = 'o', long)]
-        older_than: Option<String>,
+        #[command(flatten)]
+        filters: FilterArgs,
 
         /// Output results as JSON
         #[arg(short, long)]
         json: bool,
     },
 
+    /// Restore items gigabroom moved to the trash
+    Restore {
+        /// List trashed items without restoring anything
+        #[arg(short, long)]
+        list: bool,
+
+        /// Restore every trashed item without prompting
+        #[arg(short, long, conflicts_with = "list")]
+        all: bool,
+    },
+
+    /// Permanently delete quarantined items past their undo window
+    Purge {
+        /// Only purge quarantine runs older than this (e.g., "7d", "2w")
+        #[arg(short, long, default_value = "7d")]
+        older_than: String,
+
+        /// Preview what would be purged without deleting anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// List or restore quarantined items
+    Quarantine {
+        #[command(subcommand)]
+        action: QuarantineCommands,
+    },
+
+    /// Show past runs from the deletion journal
+    History {
+        /// Only include entries newer than this (e.g., "30d", "2w")
+        #[arg(short, long)]
+        since: Option<String>,
+
+        /// Total the space reclaimed per category for each day, week or month
+        #[arg(short, long, value_enum, value_name = "PERIOD")]
+        totals: Option<HistoryPeriod>,
+
+        /// Show at most this many runs, newest first
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Export as JSON: every journal entry, or the totals with --totals
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Clean automatically when free space drops below a threshold
+    Watchdog {
+        /// Directories to watch and clean (defaults to the configured roots, or the current directory)
+        paths: Vec<String>,
+
+        /// Free space to keep on each watched filesystem (e.g., "15%", "20GB")
+        #[arg(short, long, default_value = "15%")]
+        min_free: String,
+
+        /// Preset limiting what may be cleaned: quick, deep, nuclear, or one from the config file
+        #[arg(short, long, default_value = "quick")]
+        policy: String,
+
+        /// Keep running, checking this often (e.g., "15min", "1h", at least "30s"); checks once if omitted
+        #[arg(short, long)]
+        interval: Option<String>,
+
+        /// Maximum depth to scan (default 10, or scan.max_depth from the config)
+        #[arg(short = 'd', long)]
+        max_depth: Option<usize>,
+
+        /// Report what would be cleaned without deleting anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Install, inspect or remove a recurring cleanup (systemd user timer or crontab)
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommands,
+    },
+
+    /// Never offer or delete an artifact (recorded in the project's .gigabroom.toml)
+    Keep {
+        /// Artifacts to keep, e.g. ./build or ./.venv
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+
+    /// Let gigabroom offer an artifact again
+    Unkeep {
+        /// Artifacts previously kept with `gigabroom keep`
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+
+    /// Show, edit or check the config file (~/.config/gigabroom/config.toml)
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+
     /// Manage scan cache
     Cache {
         #[command(subcommand)]
@@ -114,6 +256,105 @@ pub enum Commands {
     },
 }
 
+/// Filters applied to scan results before they are displayed or cleaned
+#[derive(Args, Debug, Clone, Default)]
+pub struct FilterArgs {
+    /// Minimum size threshold (e.g., "100MB", "1GB")
+    #[arg(short = 's', long)]
+    pub min_size: Option<String>,
+
+    /// Only show items older than (e.g., "30d", "2w", "6m", "1y")
+    #[arg(short = 'o', long)]
+    pub older_than: Option<String>,
+
+    /// Only show items newer than (e.g., "7d", "1w")
+    #[arg(long)]
+    pub newer_than: Option<String>,
+
+    /// What the age filters measure: artifact mtime, artifact atime, or newest source file in the project
+    #[arg(long, value_enum, default_value = "mtime")]
+    pub age_source: AgeSource,
+
+    /// Don't scan paths matching this glob (repeatable); a name like "Steam" matches at any depth
+    #[arg(short = 'x', long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Only keep these categories (set by presets in the menu, not a flag)
+    #[arg(skip)]
+    pub categories: Vec<Category>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum QuarantineCommands {
+    /// List quarantine runs and what they hold
+    List,
+
+    /// Move quarantined items back to where they came from
+    Restore {
+        /// Only restore from this run (its timestamp, as shown by `quarantine list`)
+        run: Option<String>,
+
+        /// Restore every item (of the run, if given) without prompting
+        #[arg(short, long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ScheduleCommands {
+    /// Write a timer (or crontab line) that runs `gigabroom clean` unattended
+    Install {
+        /// Directories to clean (defaults to the configured roots, or the current directory)
+        paths: Vec<String>,
+
+        /// Run every hour
+        #[arg(long, conflicts_with_all = ["daily", "weekly"])]
+        hourly: bool,
+
+        /// Run every day (the default)
+        #[arg(long, conflicts_with = "weekly")]
+        daily: bool,
+
+        /// Run every week
+        #[arg(long)]
+        weekly: bool,
+
+        /// Preset deciding which categories are cleaned: quick, deep, nuclear, or one from the config file
+        #[arg(short, long, default_value = "quick")]
+        preset: String,
+
+        /// Only clean items older than this (e.g., "30d", "2w")
+        #[arg(short, long)]
+        older_than: Option<String>,
+
+        /// Quarantine items instead of deleting them (pair with `gigabroom purge`)
+        #[arg(long)]
+        quarantine: bool,
+
+        /// Use the crontab even when systemd is available
+        #[arg(long)]
+        cron: bool,
+    },
+
+    /// Show the installed schedule and when it runs next
+    Status,
+
+    /// Remove the installed schedule
+    Remove,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the settings in effect, defaults included
+    Show,
+
+    /// Open the config file in $VISUAL or $EDITOR, creating it if needed
+    Edit,
+
+    /// Check the config file for mistakes
+    Validate,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum CacheCommands {
     /// Clear the scan cache
@@ -123,6 +364,24 @@ pub enum CacheCommands {
     Info,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HistoryPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryPeriod {
+    /// chrono format string naming the period a timestamp falls in
+    pub const fn format(&self) -> &'static str {
+        match self {
+            HistoryPeriod::Day => "%Y-%m-%d",
+            HistoryPeriod::Week => "%G-W%V",
+            HistoryPeriod::Month => "%Y-%m",
+        }
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum CategoryFilter {
     /// Rust target directories
@@ -132,7 +391,7 @@ pub enum CategoryFilter {
     /// Python cache and virtual environments
     Python,
     /// PHP Composer vendor
-    PHP,
+    Php,
     /// Ruby Bundler gems
     Ruby,
     /// Java Maven target directories
@@ -148,7 +407,7 @@ pub enum CategoryFilter {
     /// Swift .build/DerivedData
     Swift,
     /// IDE caches (.idea, .vscode, .vs)
-    IDE,
+    Ide,
     /// OS junk files (.DS_Store, Thumbs.db)
     OSJunk,
     /// Temp and log files
@@ -157,6 +416,8 @@ pub enum CategoryFilter {
     PackageCache,
     /// General build/dist/out directories
     Build,
+    /// Any directory marked with a CACHEDIR.TAG
+    TaggedCache,
 }
 
 impl CategoryFilter {
@@ -165,7 +426,7 @@ impl CategoryFilter {
             CategoryFilter::Rust => Category::RustTarget,
             CategoryFilter::Node => Category::NodeModules,
             CategoryFilter::Python => Category::PythonCache,
-            CategoryFilter::PHP => Category::PHPVendor,
+            CategoryFilter::Php => Category::PHPVendor,
             CategoryFilter::Ruby => Category::RubyGems,
             CategoryFilter::JavaMaven => Category::MavenTarget,
             CategoryFilter::JavaGradle => Category::GradleBuild,
@@ -173,11 +434,12 @@ impl CategoryFilter {
             CategoryFilter::CCache => Category::CCache,
             CategoryFilter::DotNet => Category::DotNetBuild,
             CategoryFilter::Swift => Category::SwiftBuild,
-            CategoryFilter::IDE => Category::IDECache,
+            CategoryFilter::Ide => Category::IDECache,
             CategoryFilter::OSJunk => Category::OSJunk,
             CategoryFilter::Temp => Category::TempFiles,
             CategoryFilter::PackageCache => Category::PackageCache,
             CategoryFilter::Build => Category::BuildCache,
+            CategoryFilter::TaggedCache => Category::TaggedCache,
         }
     }
 }
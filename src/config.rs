@@ -0,0 +1,410 @@
+//! # Config Module
+//!
+//! User defaults loaded from `~/.config/gigabroom/config.toml`.
+//!
+//! Every setting is optional. The CLI uses the file for whatever isn't given
+//! on the command line, and the interactive menu pre-fills its prompts from
+//! it.
+//!
+//! ```toml
+//! [scan]
+//! roots = ["~/code", "~/work"]
+//! max_depth = 8
+//! min_size = "10MB"
+//! exclude = ["~/code/vendor-sdks", "Steam"]
+//! categories = ["rust", "node", "python"]
+//!
+//! [clean]
+//! mode = "trash"
+//!
+//! [cache]
+//! max_age = "14d"
+//!
+//! [retention]
+//! node = { max_age = "14d" }
+//! rust = { max_age = "30d", keep_recent = 3 }
+//! os-junk = {}
+//!
+//! [preset.web]
+//! description = "Frontend dependencies nobody touched in a month"
+//! categories = ["node", "build"]
+//! older_than = "30d"
+//! roots = ["~/web"]
+//! ```
+
+use crate::cli::CategoryFilter;
+use crate::exclude::check_patterns;
+use crate::retention::{RetentionRule, Rule};
+use crate::rules::custom_categories;
+use crate::types::{Category, DeletableItem, DeleteMode};
+use crate::ui::{CleanPreset, SafetyLevel};
+use crate::utils::{config_dir, expand_tilde, parse_duration, parse_size};
+use clap::ValueEnum;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Returns the path to the user config file
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub scan: ScanConfig,
+    pub clean: CleanConfig,
+    pub cache: CacheConfig,
+    /// Retention rules for `clean --policy`, by category name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub retention: BTreeMap<String, RetentionRule>,
+    /// User-defined presets, by the name used with `--preset`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub preset: BTreeMap<String, PresetConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Directories scanned when no path is given (defaults to the current directory)
+    pub roots: Vec<String>,
+    pub max_depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<String>,
+    /// Paths matching these globs are never scanned (like --exclude)
+    pub exclude: Vec<String>,
+    /// Only these categories are reported and cleaned (all if empty)
+    pub categories: Vec<String>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            max_depth: 10,
+            min_size: None,
+            exclude: Vec::new(),
+            categories: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CleanConfig {
+    /// What happens to items when neither --trash nor --quarantine is given
+    pub mode: DeleteMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Scan cache entries not confirmed for this long are dropped
+    pub max_age: String,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_age: "30d".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PresetConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<String>,
+    pub roots: Vec<String>,
+}
+
+/// Resolve a category name: a `--category` value or a custom rule's name
+pub fn category_named(name: &str) -> Option<Category> {
+    CategoryFilter::from_str(name, true)
+        .map(|filter| filter.to_category())
+        .ok()
+        .or_else(|| custom_categories().into_iter().find(|c| c.name().eq_ignore_ascii_case(name)))
+}
+
+impl Config {
+    /// Scan roots, tilde-expanded; the current directory if none are set
+    pub fn roots(&self) -> Vec<PathBuf> {
+        if self.scan.roots.is_empty() {
+            return vec![PathBuf::from(".")];
+        }
+        self.scan.roots.iter().map(|root| expand_tilde(root)).collect()
+    }
+
+    /// Enabled categories, or `None` when all are
+    pub fn categories(&self) -> Option<Vec<Category>> {
+        if self.scan.categories.is_empty() {
+            return None;
+        }
+        Some(self.scan.categories.iter().filter_map(|name| category_named(name)).collect())
+    }
+
+    /// The user's presets, safety judged by their riskiest category
+    pub fn presets(&self) -> Vec<CleanPreset> {
+        self.preset
+            .iter()
+            .map(|(key, preset)| {
+                let categories: Vec<Category> =
+                    preset.categories.iter().filter_map(|name| category_named(name)).collect();
+                CleanPreset {
+                    key: key.clone(),
+                    name: key.clone(),
+                    emoji: preset.emoji.clone().unwrap_or_else(|| "🔧".to_string()),
+                    description: preset
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| preset.categories.join(", ")),
+                    safety: SafetyLevel::of_all(categories.iter().copied()),
+                    categories,
+                    min_size: preset.min_size.clone(),
+                    older_than: preset.older_than.clone(),
+                    roots: preset.roots.clone(),
+                    estimated_gb: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Retention rules for categories that have one
+    pub fn retention_rules(&self) -> Vec<Rule> {
+        self.retention
+            .iter()
+            .filter_map(|(name, rule)| Rule::parse(name, category_named(name)?, rule).ok())
+            .collect()
+    }
+
+    /// How long scan cache entries are kept
+    pub fn cache_max_age(&self) -> Duration {
+        parse_duration(&self.cache.max_age).unwrap_or(Duration::from_secs(30 * 24 * 60 * 60))
+    }
+
+    /// Drop items in disabled categories
+    pub fn retain_enabled(&self, items: &mut Vec<DeletableItem>) {
+        if let Some(enabled) = self.categories() {
+            items.retain(|item| enabled.contains(&item.category));
+        }
+    }
+
+    /// Problems with the settings: errors that make a value unusable, and
+    /// warnings about values that may not do what was meant
+    pub fn check(&self) -> (Vec<String>, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        if self.scan.max_depth == 0 {
+            errors.push("scan.max_depth must be at least 1".to_string());
+        }
+        if let Some(min_size) = &self.scan.min_size {
+            if let Err(e) = parse_size(min_size) {
+                errors.push(format!("scan.min_size: {}", e));
+            }
+        }
+        if let Err(e) = parse_duration(&self.cache.max_age) {
+            errors.push(format!("cache.max_age: {}", e));
+        }
+        for name in &self.scan.categories {
+            if category_named(name).is_none() {
+                errors.push(format!("scan.categories: unknown category '{}'", name));
+            }
+        }
+
+        for (name, rule) in &self.retention {
+            match category_named(name) {
+                Some(category) => {
+                    if let Err(e) = Rule::parse(name, category, rule) {
+                        errors.push(format!("retention.{}: {}", name, e));
+                    }
+                }
+                None => errors.push(format!("retention: unknown category '{}'", name)),
+            }
+        }
+
+        let builtin: Vec<String> = CleanPreset::builtin_presets().into_iter().map(|p| p.key).collect();
+        for (key, preset) in &self.preset {
+            if builtin.iter().any(|b| b.eq_ignore_ascii_case(key)) {
+                errors.push(format!("preset.{}: '{}' is a built-in preset", key, key));
+            }
+            if preset.categories.is_empty() {
+                errors.push(format!("preset.{}: no categories", key));
+            }
+            for name in &preset.categories {
+                if category_named(name).is_none() {
+                    errors.push(format!("preset.{}: unknown category '{}'", key, name));
+                }
+            }
+            if let Some(Err(e)) = preset.min_size.as_deref().map(parse_size) {
+                errors.push(format!("preset.{}.min_size: {}", key, e));
+            }
+            if let Some(Err(e)) = preset.older_than.as_deref().map(parse_duration) {
+                errors.push(format!("preset.{}.older_than: {}", key, e));
+            }
+            for root in &preset.roots {
+                if !expand_tilde(root).is_dir() {
+                    warnings.push(format!("preset.{}.roots: {} is not a directory", key, root));
+                }
+            }
+        }
+
+        for root in &self.scan.roots {
+            if !expand_tilde(root).is_dir() {
+                warnings.push(format!("scan.roots: {} is not a directory", root));
+            }
+        }
+        if let Err(e) = check_patterns(&self.scan.exclude) {
+            errors.push(format!("scan.exclude: {}", e));
+        }
+
+        (errors, warnings)
+    }
+}
+
+/// Parse a config file and check its values
+pub fn parse_config(contents: &str) -> Result<Config, String> {
+    let config: Config = toml::from_str(contents).map_err(|e| e.to_string())?;
+    let (errors, _) = config.check();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(config)
+}
+
+/// The user's config file, read and checked once per process
+fn load() -> &'static Result<Config, String> {
+    static CONFIG: OnceLock<Result<Config, String>> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        let path = config_path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(Config::default());
+        };
+
+        parse_config(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    })
+}
+
+/// Settings from the user's config file, or why they can't be used
+///
+/// A missing file means the built-in defaults. Commands that delete use this
+/// so a malformed file stops them instead of silently changing what they do.
+pub fn try_get() -> Result<&'static Config, &'static str> {
+    load().as_ref().map_err(String::as_str)
+}
+
+/// Settings from the user's config file, read once per process
+///
+/// A missing file means the built-in defaults. A malformed file is reported
+/// on stderr and ignored, like a malformed rules file.
+pub fn get() -> &'static Config {
+    static FALLBACK: OnceLock<Config> = OnceLock::new();
+
+    try_get().unwrap_or_else(|e| {
+        FALLBACK.get_or_init(|| {
+            eprintln!(
+                "{} ignoring {} (check it with `gigabroom config validate`)",
+                "Warning:".yellow().bold(),
+                e
+            );
+            Config::default()
+        })
+    })
+}
+
+/// Written by `gigabroom config edit` when there's no config file yet
+pub const TEMPLATE: &str = r#"# gigabroom configuration
+# Everything is optional; command-line flags always win.
+
+[scan]
+# Directories scanned when no path is given
+# roots = ["~/code"]
+# max_depth = 10
+# min_size = "10MB"
+# Never scan paths matching these globs; a name without a slash matches at
+# any depth. Per-directory .gigabroomignore files (gitignore syntax) work too.
+# exclude = ["~/code/vendor-sdks", "Steam"]
+# Only report and clean these categories (names as for --category)
+# categories = ["rust", "node", "python"]
+
+[clean]
+# delete, trash or quarantine
+# mode = "delete"
+
+[cache]
+# Scan cache entries unused for this long are dropped
+# max_age = "30d"
+
+# How long each category is kept, for `clean --policy`. Every condition of a
+# rule must hold; a rule without conditions always applies.
+# [retention]
+# node = { max_age = "14d" }                    # 14 days without project activity
+# rust = { max_age = "30d", keep_recent = 3 }   # ...but never the 3 most active projects
+# python = { max_age = "90d", min_size = "50MB" }
+# os-junk = {}
+
+# Your own presets, used with `clean --preset web` and in the Presets menu
+# [preset.web]
+# description = "Frontend dependencies nobody touched in a month"
+# categories = ["node", "build"]
+# older_than = "30d"
+# roots = ["~/web"]
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(parse_config(TEMPLATE).unwrap(), Config::default());
+
+        let config = parse_config(
+            r#"
+            [scan]
+            max_depth = 4
+            categories = ["rust", "Node"]
+
+            [clean]
+            mode = "trash"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.scan.max_depth, 4);
+        assert_eq!(config.clean.mode, DeleteMode::Trash);
+        assert_eq!(config.categories(), Some(vec![Category::RustTarget, Category::NodeModules]));
+        assert_eq!(config.cache_max_age(), Duration::from_secs(30 * 24 * 60 * 60));
+
+        let config = parse_config("[preset.web]\ncategories = [\"node\", \"build\"]\nolder_than = \"30d\"").unwrap();
+        let presets = config.presets();
+        assert_eq!(presets[0].key, "web");
+        assert_eq!(presets[0].categories, vec![Category::NodeModules, Category::BuildCache]);
+        assert_eq!(presets[0].safety, SafetyLevel::Moderate);
+        let config = parse_config("[retention]\nnode = { max_age = \"14d\" }\nos-junk = {}").unwrap();
+        let rules = config.retention_rules();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].category, Category::NodeModules);
+        assert_eq!(rules[0].max_age, Some(Duration::from_secs(14 * 24 * 60 * 60)));
+        assert!(parse_config("[retention]\nnode = { max_age = \"soon\" }").is_err());
+
+        assert!(parse_config("[preset.quick]\ncategories = [\"node\"]").is_err());
+
+        assert!(parse_config("[scan]\ncategories = [\"cobol\"]").is_err());
+        assert!(parse_config("[cache]\nmax_age = \"soon\"").is_err());
+        assert!(parse_config("[scan]\nmax_dept = 4").is_err());
+        assert!(parse_config("[scan]\nexclude = [\"[oops\"]").is_err());
+    }
+}
@@ -0,0 +1,508 @@
+//! # Detectors Module
+//!
+//! Pluggable detection of deletable build artifacts.
+//!
+//! Each ecosystem is described by a [`Detector`]: the names it matches, the
+//! marker files that confirm a match, the category it reports and a priority.
+//! The [`DetectorRegistry`] resolves a path against every detector, so
+//! ambiguous names like `vendor`, `target` and `build` are settled by
+//! explicit priority instead of match-arm order.
+
+use crate::types::Category;
+use crate::utils::{has_cachedir_tag, is_cargo_target};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// A rule that recognizes one kind of deletable artifact.
+pub trait Detector: Send + Sync {
+    /// Category reported for paths this detector accepts
+    fn category(&self) -> Category;
+
+    /// Detectors with a higher priority are tried first when several match
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Cheap check on the file name (and full path) alone
+    fn matches_name(&self, file_name: &str, path: &Path) -> bool;
+
+    /// Confirm a name match by checking entry type and marker files
+    fn confirm(&self, _path: &Path, _is_dir: bool) -> bool {
+        true
+    }
+
+    /// Exact names an index (Spotlight, locate) can be queried for
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn indexed_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Table-driven detector used for all built-in ecosystems
+pub struct BuiltinDetector {
+    /// Exact file or directory names
+    pub names: &'static [&'static str],
+    /// File name suffixes (e.g. ".pyc")
+    pub suffixes: &'static [&'static str],
+    /// Fragments that must appear in the full path (e.g. "/.npm/_cacache")
+    pub path_fragments: &'static [&'static str],
+    /// Only match directories
+    pub dir_only: bool,
+    /// Marker-file predicate that must hold for the match to count
+    pub marker: Option<fn(&Path) -> bool>,
+    pub category: Category,
+    pub priority: i32,
+}
+
+impl BuiltinDetector {
+    const fn dirs(names: &'static [&'static str], category: Category) -> Self {
+        Self {
+            names,
+            suffixes: &[],
+            path_fragments: &[],
+            dir_only: true,
+            marker: None,
+            category,
+            priority: 0,
+        }
+    }
+
+    const fn files(names: &'static [&'static str], category: Category) -> Self {
+        Self {
+            dir_only: false,
+            ..Self::dirs(names, category)
+        }
+    }
+
+    const fn suffixes(suffixes: &'static [&'static str], category: Category) -> Self {
+        Self {
+            suffixes,
+            ..Self::files(&[], category)
+        }
+    }
+
+    const fn path_fragments(path_fragments: &'static [&'static str], category: Category) -> Self {
+        Self {
+            path_fragments,
+            ..Self::files(&[], category)
+        }
+    }
+
+    const fn with_marker(self, marker: fn(&Path) -> bool, priority: i32) -> Self {
+        Self {
+            marker: Some(marker),
+            priority,
+            ..self
+        }
+    }
+
+    const fn with_priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+}
+
+impl Detector for BuiltinDetector {
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn matches_name(&self, file_name: &str, path: &Path) -> bool {
+        if self.names.contains(&file_name) {
+            return true;
+        }
+
+        if self.suffixes.iter().any(|suffix| file_name.ends_with(suffix)) {
+            return true;
+        }
+
+        !self.path_fragments.is_empty()
+            && path
+                .to_str()
+                .map(|p| self.path_fragments.iter().any(|fragment| p.contains(fragment)))
+                .unwrap_or(false)
+    }
+
+    fn confirm(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        self.marker.map(|marker| marker(path)).unwrap_or(true)
+    }
+
+    fn indexed_names(&self) -> Vec<String> {
+        self.names.iter().map(|name| (*name).to_string()).collect()
+    }
+}
+
+/// Detects any directory carrying a valid CACHEDIR.TAG
+///
+/// Runs after every name-based detector, so a tagged Rust `target/` is still
+/// reported as a Rust target.
+pub struct CacheDirTagDetector;
+
+impl Detector for CacheDirTagDetector {
+    fn category(&self) -> Category {
+        Category::TaggedCache
+    }
+
+    fn priority(&self) -> i32 {
+        -20
+    }
+
+    fn matches_name(&self, _file_name: &str, _path: &Path) -> bool {
+        true
+    }
+
+    fn confirm(&self, path: &Path, is_dir: bool) -> bool {
+        is_dir && has_cachedir_tag(path)
+    }
+}
+
+/// Ordered collection of detectors
+pub struct DetectorRegistry {
+    detectors: Vec<Box<dyn Detector>>,
+}
+
+impl DetectorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self { detectors: Vec::new() }
+    }
+
+    /// Create a registry holding every built-in detector
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for detector in builtin_detectors() {
+            registry.register(Box::new(detector));
+        }
+        registry.register(Box::new(CacheDirTagDetector));
+        registry
+    }
+
+    /// Add a detector, keeping the list sorted by descending priority
+    ///
+    /// Detectors with equal priority keep their registration order.
+    pub fn register(&mut self, detector: Box<dyn Detector>) {
+        let position = self
+            .detectors
+            .iter()
+            .position(|d| d.priority() < detector.priority())
+            .unwrap_or(self.detectors.len());
+        self.detectors.insert(position, detector);
+    }
+
+    /// Determine if a path is deletable and return its category
+    pub fn detect(&self, path: &Path) -> Option<Category> {
+        let file_name = path.file_name()?.to_str()?;
+        let is_dir = path.is_dir();
+
+        self.detectors
+            .iter()
+            .filter(|d| d.matches_name(file_name, path))
+            .find(|d| d.confirm(path, is_dir))
+            .map(|d| d.category())
+    }
+
+    /// Unique names that indexed backends should query for
+    #[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+    pub fn indexed_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.detectors.iter().flat_map(|d| d.indexed_names()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+impl Default for DetectorRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+static REGISTRY: OnceLock<DetectorRegistry> = OnceLock::new();
+
+/// The process-wide detector registry
+///
+/// Holds the built-in detectors plus any user rules from the rules file.
+pub fn registry() -> &'static DetectorRegistry {
+    REGISTRY.get_or_init(|| {
+        let mut registry = DetectorRegistry::with_builtins();
+        for rule in crate::rules::loaded_rules() {
+            registry.register(Box::new(rule.clone()));
+        }
+        registry
+    })
+}
+
+/// Built-in detectors for every supported ecosystem
+fn builtin_detectors() -> Vec<BuiltinDetector> {
+    vec![
+        // Rust
+        BuiltinDetector::dirs(&["target"], Category::RustTarget).with_marker(is_cargo_target, 20),
+
+        // JavaScript/TypeScript
+        BuiltinDetector::dirs(&["node_modules"], Category::NodeModules),
+
+        // Python
+        BuiltinDetector::dirs(&["__pycache__", ".pytest_cache", ".tox", "venv", ".venv"], Category::PythonCache),
+        BuiltinDetector::suffixes(&[".pyc", ".pyo"], Category::PythonCache),
+
+        // Java - Maven
+        BuiltinDetector::dirs(&["target"], Category::MavenTarget).with_marker(is_maven_target, 10),
+
+        // Java - Gradle (wins over the generic build directory)
+        BuiltinDetector::dirs(&["build"], Category::GradleBuild).with_marker(is_gradle_build, 10),
+        BuiltinDetector::dirs(&[".gradle"], Category::GradleBuild),
+
+        // PHP, Go and Ruby all use "vendor" - Composer wins, then Go, then Bundler
+        BuiltinDetector::dirs(&["vendor"], Category::PHPVendor).with_marker(is_composer_vendor, 30),
+        BuiltinDetector::dirs(&["vendor"], Category::GoVendor).with_marker(is_go_vendor, 20),
+        BuiltinDetector::dirs(&["vendor"], Category::RubyGems).with_marker(is_ruby_bundler, 10),
+        BuiltinDetector::dirs(&[".bundle"], Category::RubyGems),
+
+        // C/C++
+        BuiltinDetector::dirs(&["CMakeFiles"], Category::CCache),
+        BuiltinDetector::files(&["a.out"], Category::CCache),
+        BuiltinDetector::suffixes(&[".o", ".a"], Category::CCache),
+
+        // .NET
+        BuiltinDetector::dirs(&["bin", "obj"], Category::DotNetBuild).with_marker(is_dotnet_build, 10),
+        BuiltinDetector::dirs(&["packages"], Category::DotNetBuild).with_marker(is_dotnet_packages, 10),
+
+        // Swift
+        BuiltinDetector::dirs(&[".build"], Category::SwiftBuild).with_marker(is_swift_build, 10),
+        BuiltinDetector::dirs(&["DerivedData"], Category::SwiftBuild),
+
+        // IDE Caches
+        BuiltinDetector::dirs(&[".idea", ".vscode", ".vs"], Category::IDECache),
+
+        // OS Junk Files
+        BuiltinDetector::files(&[".DS_Store", "Thumbs.db", "desktop.ini", ".localized"], Category::OSJunk),
+
+        // Temp/Cache directories and files
+        BuiltinDetector::dirs(&[".sass-cache", ".parcel-cache", ".cache"], Category::TempFiles),
+        BuiltinDetector::suffixes(&[".log", ".tmp", ".temp"], Category::TempFiles),
+
+        // Package manager global caches (DANGEROUS!) - only when no name matched
+        BuiltinDetector::path_fragments(
+            &["/.npm/_cacache", "/.cache/pip", "/.cache/yarn", "/.m2/repository"],
+            Category::PackageCache,
+        )
+        .with_priority(-10),
+
+        // General build (lowest priority - only when nothing more specific matches)
+        BuiltinDetector::dirs(&["build", "dist", "out"], Category::BuildCache),
+    ]
+}
+
+/// Check if a path is a Maven target directory
+#[inline]
+fn is_maven_target(path: &Path) -> bool {
+    path.parent()
+        .map(|p| p.join("pom.xml").exists())
+        .unwrap_or(false)
+}
+
+/// Check if a path is a Gradle build directory
+#[inline]
+fn is_gradle_build(path: &Path) -> bool {
+    path.parent()
+        .map(|p| p.join("build.gradle").exists() || p.join("build.gradle.kts").exists())
+        .unwrap_or(false)
+}
+
+/// Check if a path is a PHP Composer vendor directory
+#[inline]
+fn is_composer_vendor(path: &Path) -> bool {
+    path.parent()
+        .map(|p| p.join("composer.json").exists())
+        .unwrap_or(false)
+}
+
+/// Check if a path is a Go vendor directory
+#[inline]
+fn is_go_vendor(path: &Path) -> bool {
+    path.parent()
+        .map(|p| p.join("go.mod").exists() || p.join("go.sum").exists())
+        .unwrap_or(false)
+}
+
+/// Check if a path is a .NET build directory (bin/obj)
+#[inline]
+fn is_dotnet_build(path: &Path) -> bool {
+    let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    path.parent()
+        .map(|p| {
+            // First check: parent must have a .NET project file
+            let has_project_file = p.read_dir()
+                .ok()
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .any(|e| {
+                            e.file_name()
+                                .to_str()
+                                .map(|s| {
+                                    s.ends_with(".csproj")
+                                        || s.ends_with(".vbproj")
+                                        || s.ends_with(".fsproj")
+                                })
+                                .unwrap_or(false)
+                        })
+                })
+                .unwrap_or(false);
+
+            if !has_project_file {
+                return false;
+            }
+
+            // Second check: BOTH bin and obj should exist (typical .NET structure)
+            // This prevents false positives with system directories
+            if dir_name == "bin" {
+                p.join("obj").exists()
+            } else if dir_name == "obj" {
+                p.join("bin").exists()
+            } else {
+                false
+            }
+        })
+        .unwrap_or(false)
+}
+
+/// Check if a path is a .NET packages directory
+#[inline]
+fn is_dotnet_packages(path: &Path) -> bool {
+    // packages/ directory is typically in solution root
+    path.parent()
+        .and_then(|p| p.parent())
+        .map(|p| {
+            p.read_dir()
+                .ok()
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .any(|e| {
+                            e.file_name()
+                                .to_str()
+                                .map(|s| s.ends_with(".sln"))
+                                .unwrap_or(false)
+                        })
+                })
+                .unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+/// Check if a path is a Swift build directory
+#[inline]
+fn is_swift_build(path: &Path) -> bool {
+    path.parent()
+        .map(|p| p.join("Package.swift").exists())
+        .unwrap_or(false)
+}
+
+/// Check if a path is a Ruby Bundler vendor directory
+#[inline]
+fn is_ruby_bundler(path: &Path) -> bool {
+    // Check if parent has Gemfile
+    path.parent()
+        .map(|p| p.join("Gemfile").exists() || p.join("Gemfile.lock").exists())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_project(name: &str, markers: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("gigabroom-detectors-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        for marker in markers {
+            fs::write(root.join(marker), "").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_vendor_resolved_by_priority() {
+        let registry = DetectorRegistry::with_builtins();
+
+        // Composer and Go markers together: Composer has the higher priority
+        let root = temp_project("vendor", &["composer.json", "go.mod"]);
+        fs::create_dir_all(root.join("vendor")).unwrap();
+        assert_eq!(registry.detect(&root.join("vendor")), Some(Category::PHPVendor));
+
+        fs::remove_file(root.join("composer.json")).unwrap();
+        assert_eq!(registry.detect(&root.join("vendor")), Some(Category::GoVendor));
+
+        fs::remove_file(root.join("go.mod")).unwrap();
+        assert_eq!(registry.detect(&root.join("vendor")), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_build_prefers_gradle_over_generic() {
+        let registry = DetectorRegistry::with_builtins();
+
+        let root = temp_project("build", &["build.gradle"]);
+        fs::create_dir_all(root.join("build")).unwrap();
+        assert_eq!(registry.detect(&root.join("build")), Some(Category::GradleBuild));
+
+        fs::remove_file(root.join("build.gradle")).unwrap();
+        assert_eq!(registry.detect(&root.join("build")), Some(Category::BuildCache));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cachedir_tag() {
+        let registry = DetectorRegistry::with_builtins();
+        let root = temp_project("cachedir", &[]);
+        let cache = root.join("ccache");
+        fs::create_dir_all(&cache).unwrap();
+        assert_eq!(registry.detect(&cache), None);
+
+        // A tag without the signature doesn't count
+        fs::write(cache.join("CACHEDIR.TAG"), "not a tag").unwrap();
+        assert_eq!(registry.detect(&cache), None);
+
+        fs::write(cache.join("CACHEDIR.TAG"), "Signature: 8a477f597d28d172789f06886806bc55\n").unwrap();
+        assert_eq!(registry.detect(&cache), Some(Category::TaggedCache));
+
+        // Cargo's tag confirms a target directory beside a Cargo.toml; on its
+        // own it's just another tagged cache
+        let target = root.join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(
+            target.join("CACHEDIR.TAG"),
+            "Signature: 8a477f597d28d172789f06886806bc55\n# This file is a cache directory tag created by cargo.\n",
+        )
+        .unwrap();
+        assert_eq!(registry.detect(&target), Some(Category::TaggedCache));
+        fs::write(root.join("Cargo.toml"), "").unwrap();
+        assert_eq!(registry.detect(&target), Some(Category::RustTarget));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_dir_only_detectors_ignore_files() {
+        let registry = DetectorRegistry::with_builtins();
+
+        let root = temp_project("files", &["node_modules", "app.log"]);
+        assert_eq!(registry.detect(&root.join("node_modules")), None);
+        assert_eq!(registry.detect(&root.join("app.log")), Some(Category::TempFiles));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,203 @@
+//! # Disk Module
+//!
+//! Free space per filesystem, so reclaimable bytes can be put in context.
+//!
+//! Items are grouped by the mount point they live on: freeing 30 GB under
+//! `/home` does nothing for a full `/var`. Usage comes straight from
+//! `statvfs`, which works the same on Linux and macOS.
+
+use crate::types::DeletableItem;
+use crate::utils::{absolute_path, device_of, format_size, mount_top};
+use colored::*;
+use std::collections::hash_map::{Entry, HashMap};
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Size and free space of one filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsUsage {
+    pub total: u64,
+    /// Space available to unprivileged users (excludes root's reserve)
+    pub available: u64,
+}
+
+impl FsUsage {
+    pub fn free_percent(&self, available: u64) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        available as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// Usage of the filesystem `path` is on
+pub fn usage(path: &Path) -> Option<FsUsage> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    // Block counts are in units of f_frsize; their integer widths vary by platform
+    #[allow(clippy::unnecessary_cast)]
+    let (blocks, available, fragment) = (stat.f_blocks as u64, stat.f_bavail as u64, stat.f_frsize as u64);
+    Some(FsUsage {
+        total: blocks * fragment,
+        available: available * fragment,
+    })
+}
+
+/// One filesystem's share of a set of items
+#[derive(Debug, Clone)]
+pub struct MountShare {
+    pub mount: PathBuf,
+    pub usage: FsUsage,
+    pub reclaimable: u64,
+    pub items: usize,
+}
+
+/// Group items by the filesystem they live on, largest reclaimable first
+///
+/// Items that no longer exist or whose filesystem can't be queried are left out.
+pub fn by_mount<'a>(items: impl IntoIterator<Item = &'a DeletableItem>) -> Vec<MountShare> {
+    let mut shares: HashMap<u64, MountShare> = HashMap::new();
+
+    for item in items {
+        // The item itself, not a symlink's target, is what gets removed
+        let Ok(real) = absolute_path(&item.path) else {
+            continue;
+        };
+        let Some(dev) = device_of(&real) else {
+            continue;
+        };
+
+        let share = match shares.entry(dev) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mount = mount_top(&real, dev);
+                let Some(usage) = usage(&mount) else {
+                    continue;
+                };
+                entry.insert(MountShare {
+                    mount,
+                    usage,
+                    reclaimable: 0,
+                    items: 0,
+                })
+            }
+        };
+        share.reclaimable += item.size;
+        share.items += 1;
+    }
+
+    let mut shares: Vec<MountShare> = shares.into_values().collect();
+    shares.sort_by(|a, b| b.reclaimable.cmp(&a.reclaimable).then_with(|| a.mount.cmp(&b.mount)));
+    shares
+}
+
+fn print_heading(title: &str) {
+    println!("\n{}", "═".repeat(80).bright_blue());
+    println!("  {}", title.bright_blue().bold());
+    println!("{}", "═".repeat(80).bright_blue());
+}
+
+fn print_mount(share: &MountShare) {
+    println!(
+        "\n{} {}",
+        share.mount.display().to_string().bright_white().bold(),
+        format!("({} total, {} items)", format_size(share.usage.total), share.items).dimmed()
+    );
+}
+
+/// Show free space now and after reclaiming the items, per filesystem
+pub fn show_disk_space(items: &[DeletableItem]) {
+    let shares = by_mount(items);
+    if shares.is_empty() {
+        return;
+    }
+
+    print_heading("💾 DISK SPACE CONTEXT");
+
+    for share in &shares {
+        let usage = share.usage;
+        let after = usage.available + share.reclaimable;
+
+        print_mount(share);
+        println!(
+            "  Available:   {} ({:.1}% free)",
+            format_size(usage.available).bright_green(),
+            usage.free_percent(usage.available)
+        );
+        println!(
+            "  Reclaimable: {} → {} ({:.1}% free)",
+            format_size(share.reclaimable).bright_cyan().bold(),
+            format_size(after).bright_green().bold(),
+            usage.free_percent(after)
+        );
+    }
+
+    println!("{}", "═".repeat(80).bright_blue());
+}
+
+/// Show how free space changed on each filesystem since `before` was taken
+pub fn show_space_change(before: &[MountShare]) {
+    if before.is_empty() {
+        return;
+    }
+
+    print_heading("💾 FREE SPACE");
+
+    for share in before {
+        let Some(now) = usage(&share.mount) else {
+            continue;
+        };
+        let was = share.usage.available;
+
+        print_mount(share);
+        println!(
+            "  Before:  {} ({:.1}% free)",
+            format_size(was),
+            share.usage.free_percent(was)
+        );
+        println!(
+            "  After:   {} ({:.1}% free)  {}",
+            format_size(now.available).bright_green().bold(),
+            now.free_percent(now.available),
+            format!("+{}", format_size(now.available.saturating_sub(was))).bright_cyan()
+        );
+    }
+
+    println!("{}", "═".repeat(80).bright_blue());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Category;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_by_mount_groups_items() {
+        let dir = std::env::temp_dir().join(format!("gigabroom-disk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.log"), "a").unwrap();
+        std::fs::write(dir.join("b.log"), "b").unwrap();
+
+        let item = |name: &str, size| {
+            DeletableItem::new(dir.join(name), size, Category::TempFiles, "tmp".to_string(), SystemTime::now())
+        };
+
+        // Missing items are left out; the rest share one filesystem
+        let items = vec![item("a.log", 10), item("b.log", 5), item("gone.log", 100)];
+        let shares = by_mount(&items);
+
+        assert_eq!(shares.len(), 1);
+        assert_eq!(shares[0].items, 2);
+        assert_eq!(shares[0].reclaimable, 15);
+        assert!(shares[0].usage.total > 0);
+        assert!(dir.starts_with(&shares[0].mount));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
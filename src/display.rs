@@ -1,5 +1,5 @@
 /// Display and formatting functions
-use crate::types::{Category, DeletableItem};
+use crate::types::{Category, DeletableItem, GitState};
 use crate::utils::format_size;
 use crate::ui;
 use colored::*;
@@ -26,14 +26,18 @@ pub fn display_scan_results(items: &[DeletableItem], verbose: bool, _quiet: bool
         ui::clear_screen();
     }
 
+    // Kept items are listed on their own, after everything that can be cleaned
+    let (kept, items): (Vec<&DeletableItem>, Vec<&DeletableItem>) = items.iter().partition(|item| item.kept.is_some());
+
     if items.is_empty() {
         println!("\n{}", "No deletable items found!".green().bold());
+        display_kept(&kept, verbose);
         return;
     }
 
     // Group items by category
     let mut category_groups: HashMap<Category, Vec<&DeletableItem>> = HashMap::new();
-    for item in items {
+    for item in &items {
         category_groups.entry(item.category).or_default().push(item);
     }
 
@@ -46,6 +50,14 @@ pub fn display_scan_results(items: &[DeletableItem], verbose: bool, _quiet: bool
         format_size(total_size).bright_green().bold()
     );
 
+    let in_use = items.iter().filter(|item| item.in_use.is_some()).count();
+    if in_use > 0 {
+        println!("{} {}",
+            format!("⏳ {} items are in use by running processes or builds", in_use).bright_yellow(),
+            "(skipped when cleaning unless --force-in-use)".dimmed()
+        );
+    }
+
     // Sort category groups by total size (descending)
     let mut sorted_categories: Vec<_> = category_groups.iter().collect();
     sorted_categories.sort_by(|a, b| {
@@ -70,8 +82,8 @@ pub fn display_scan_results(items: &[DeletableItem], verbose: bool, _quiet: bool
         );
 
         // Sort items within category by size (largest first)
-        let mut sorted_items: Vec<_> = category_items.iter().copied().collect();
-        sorted_items.sort_by(|a, b| b.size.cmp(&a.size));
+        let mut sorted_items: Vec<_> = category_items.to_vec();
+        sorted_items.sort_by_key(|item| std::cmp::Reverse(item.size));
 
         // Display items (show max 5)
         let display_count = if sorted_items.len() <= 5 { sorted_items.len() } else { 5 };
@@ -80,14 +92,40 @@ pub fn display_scan_results(items: &[DeletableItem], verbose: bool, _quiet: bool
             let bar_width = 20;
             let bar = ui::progress_bar(item.size, category_size, bar_width);
 
+            let git_marker = match item.git {
+                GitState::Tracked => format!("  {}", "🔒 tracked in git".bright_red()),
+                GitState::Unverified => format!("  {}", "🔒 git unavailable".bright_red()),
+                GitState::Ignored => format!("  {}", "✓ git-ignored".green().dimmed()),
+                _ => String::new(),
+            };
+
+            let in_use_marker = match &item.in_use {
+                Some(reason) => format!("  {}", format!("⏳ in use: {}", reason).bright_yellow()),
+                None => String::new(),
+            };
+
             println!(
-                "  {:2}. {:30} {:>10}  {}",
+                "  {:2}. {:30} {:>10}  {}{}{}",
                 idx + 1,
                 item.project_name.chars().take(30).collect::<String>().bright_cyan(),
                 format_size(item.size),
-                bar
+                bar,
+                git_marker,
+                in_use_marker
             );
 
+            if item.shared_by.len() > 1 {
+                let mut names = item.shared_by.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+                if item.shared_by.len() > 5 {
+                    names.push_str(", …");
+                }
+                println!(
+                    "      {} {}",
+                    format!("shared by {} crates:", item.shared_by.len()).dimmed(),
+                    names.dimmed()
+                );
+            }
+
             if verbose {
                 println!("      {}", item.path.display().to_string().dimmed());
             }
@@ -97,6 +135,37 @@ pub fn display_scan_results(items: &[DeletableItem], verbose: bool, _quiet: bool
             println!("      {} {} more items", "...and".dimmed(), (sorted_items.len() - display_count).to_string().bright_yellow());
         }
     }
+
+    display_kept(&kept, verbose);
+}
+
+/// List items a project's `.gigabroom.toml` keeps
+fn display_kept(kept: &[&DeletableItem], verbose: bool) {
+    if kept.is_empty() {
+        return;
+    }
+
+    let kept_size: u64 = kept.iter().map(|item| item.size).sum();
+    println!("\n🔒 {} • {} • {} items {}",
+        "Kept".bright_white().bold(),
+        format_size(kept_size).bright_green().bold(),
+        kept.len(),
+        "(never offered; `gigabroom unkeep <path>` to release)".dimmed()
+    );
+
+    for (idx, item) in kept.iter().enumerate() {
+        println!(
+            "  {:2}. {:30} {:>10}  {}",
+            idx + 1,
+            item.project_name.chars().take(30).collect::<String>().bright_cyan(),
+            format_size(item.size),
+            format!("🔒 {}", item.category.name()).dimmed()
+        );
+
+        if verbose {
+            println!("      {}", item.path.display().to_string().dimmed());
+        }
+    }
 }
 
 /// Get emoji for a category
@@ -117,5 +186,7 @@ fn get_category_emoji(category: &Category) -> &str {
         Category::TempFiles => "📝",
         Category::PackageCache => "⚠️",
         Category::BuildCache => "📁",
+        Category::TaggedCache => "🏷️",
+        Category::Custom(_) => "🔧",
     }
 }
@@ -0,0 +1,148 @@
+//! # Exclude Module
+//!
+//! Paths the scanner never walks into.
+//!
+//! Exclusions come from `--exclude` globs, `scan.exclude` in the config file
+//! and `.gigabroomignore` files. A glob without a slash matches a name at any
+//! depth (`Steam`, `*.vdi`); one with a slash matches the full path, with `~`
+//! expanded (`~/.local/share/Steam`). Ignore files use gitignore syntax and
+//! apply to the directory they live in and everything below it; deeper files
+//! take precedence, so `!keep-me` can re-include something.
+
+use crate::utils::expand_tilde;
+use colored::*;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Per-directory ignore file
+pub const IGNORE_FILE: &str = ".gigabroomignore";
+
+/// Compile one exclude pattern into a glob over absolute paths
+fn compile(pattern: &str) -> Result<Glob, String> {
+    let trimmed = pattern.trim().trim_end_matches('/');
+    let expanded = expand_tilde(trimmed).to_string_lossy().into_owned();
+
+    let full = if expanded.starts_with('/') || expanded.starts_with("**") {
+        expanded
+    } else {
+        format!("**/{}", expanded)
+    };
+
+    Glob::new(&full).map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))
+}
+
+/// Check exclude patterns without building anything
+pub fn check_patterns(patterns: &[String]) -> Result<(), String> {
+    patterns.iter().try_for_each(|pattern| compile(pattern).map(|_| ()))
+}
+
+fn load_ignore_file(dir: &Path) -> Option<Gitignore> {
+    let path = dir.join(IGNORE_FILE);
+    if !path.is_file() {
+        return None;
+    }
+
+    // Reported on stderr so JSON output stays clean; valid lines still apply
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&path) {
+        eprintln!("{} {}: {}", "Warning:".yellow().bold(), path.display(), e);
+    }
+    builder
+        .build()
+        .map_err(|e| eprintln!("{} ignoring {}: {}", "Warning:".yellow().bold(), path.display(), e))
+        .ok()
+}
+
+/// Exclude globs plus the ignore files seen so far
+///
+/// All paths passed in must be absolute.
+pub struct Excludes {
+    globs: GlobSet,
+    /// Ignore file of each directory looked at (None if it has none)
+    ignore_files: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl Excludes {
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(compile(pattern)?);
+        }
+
+        Ok(Self {
+            globs: builder.build().map_err(|e| e.to_string())?,
+            ignore_files: HashMap::new(),
+        })
+    }
+
+    /// Record a directory the walk listed, and whether it has an ignore file
+    pub fn visit(&mut self, dir: &Path, has_ignore_file: bool) {
+        let ignore = if has_ignore_file { load_ignore_file(dir) } else { None };
+        self.ignore_files.insert(dir.to_path_buf(), ignore);
+    }
+
+    /// Whether `path` itself is excluded
+    ///
+    /// Ignore files of directories the walk hasn't listed (those above the
+    /// scan root) are read on first use.
+    pub fn is_excluded(&mut self, path: &Path, is_dir: bool) -> bool {
+        if self.globs.is_match(path) {
+            return true;
+        }
+
+        for dir in path.ancestors().skip(1) {
+            let ignore = self
+                .ignore_files
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| load_ignore_file(dir));
+
+            match ignore.as_ref().map(|ignore| ignore.matched(path, is_dir)) {
+                Some(Match::Ignore(_)) => return true,
+                Some(Match::Whitelist(_)) => return false,
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    /// Whether `path` or any directory above it is excluded, for items
+    /// found without walking (from the system index)
+    pub fn is_excluded_tree(&mut self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        path.ancestors()
+            .enumerate()
+            .any(|(n, p)| self.is_excluded(p, n > 0 || is_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excludes() {
+        let dir = std::env::temp_dir().join(format!("gigabroom-exclude-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join(IGNORE_FILE), "backups/\n*.iso\n").unwrap();
+        std::fs::write(dir.join("sub").join(IGNORE_FILE), "!keep.iso\n").unwrap();
+
+        let mut excludes = Excludes::new(&["Steam".to_string(), "/mnt/**".to_string()]).unwrap();
+        assert!(excludes.is_excluded(Path::new("/home/me/.local/share/Steam"), true));
+        assert!(excludes.is_excluded(Path::new("/mnt/backup"), true));
+        assert!(!excludes.is_excluded(Path::new("/home/me/Steamy"), true));
+
+        assert!(excludes.is_excluded(&dir.join("backups"), true));
+        assert!(!excludes.is_excluded(&dir.join("backups"), false));
+        assert!(excludes.is_excluded(&dir.join("sub/disk.iso"), false));
+        assert!(!excludes.is_excluded(&dir.join("sub/keep.iso"), false));
+        assert!(excludes.is_excluded_tree(&dir.join("backups/old/target")));
+
+        assert!(Excludes::new(&["[oops".to_string()]).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
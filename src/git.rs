@@ -0,0 +1,142 @@
+//! # Git Module
+//!
+//! Git-aware safety checks for scan results.
+//!
+//! Generic names like `build`, `dist`, `out` and `vendor` can hold committed
+//! source. Every item inside a git work tree is checked against the index:
+//! items containing tracked files are protected from deletion, and items git
+//! reports as ignored are flagged as high-confidence artifacts. When git is
+//! missing or fails, items in the work tree are protected as well.
+
+use crate::types::{DeletableItem, GitState};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Maximum number of paths passed to a single `git ls-files` invocation
+const LS_FILES_CHUNK: usize = 256;
+
+/// Find the root of the git work tree containing a path
+pub fn find_work_tree(path: &Path) -> Option<PathBuf> {
+    path.parent()?
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Fill in the git state of every item
+///
+/// Items are grouped by work tree so each repository costs a couple of git
+/// invocations regardless of how many items it holds.
+pub fn annotate_git_state(items: &mut [DeletableItem]) {
+    let roots: Vec<Option<PathBuf>> = items.par_iter().map(|item| find_work_tree(&item.path)).collect();
+
+    let mut by_repo: HashMap<PathBuf, Vec<(usize, PathBuf)>> = HashMap::new();
+    for (idx, root) in roots.into_iter().enumerate() {
+        match root {
+            Some(root) => by_repo.entry(root).or_default().push((idx, items[idx].path.clone())),
+            None => items[idx].git = GitState::NotInRepo,
+        }
+    }
+
+    let states: Vec<(usize, GitState)> = by_repo
+        .par_iter()
+        .flat_map(|(root, paths)| repo_states(root, paths))
+        .collect();
+
+    for (idx, state) in states {
+        items[idx].git = state;
+    }
+}
+
+/// Resolve the git state of paths that all live in the same work tree
+fn repo_states(root: &Path, paths: &[(usize, PathBuf)]) -> Vec<(usize, GitState)> {
+    let relative: Vec<(usize, PathBuf)> = paths
+        .iter()
+        .filter_map(|(idx, path)| path.strip_prefix(root).ok().map(|rel| (*idx, rel.to_path_buf())))
+        .collect();
+
+    let (Some(tracked), Some(ignored)) = (tracked_files(root, &relative), ignored_paths(root, &relative)) else {
+        return paths.iter().map(|(idx, _)| (*idx, GitState::Unverified)).collect();
+    };
+
+    relative
+        .into_iter()
+        .map(|(idx, rel)| {
+            let state = if tracked.iter().any(|file| file.starts_with(&rel)) {
+                GitState::Tracked
+            } else if ignored.contains(&rel) {
+                GitState::Ignored
+            } else {
+                GitState::Untracked
+            };
+            (idx, state)
+        })
+        .collect()
+}
+
+/// Files tracked by git under any of the given paths
+fn tracked_files(root: &Path, relative: &[(usize, PathBuf)]) -> Option<Vec<PathBuf>> {
+    let mut tracked = Vec::new();
+
+    for chunk in relative.chunks(LS_FILES_CHUNK) {
+        // Literal pathspecs, so directory names with `*`, `?` or `[` aren't globs
+        let output = Command::new("git")
+            .arg("--literal-pathspecs")
+            .arg("-C")
+            .arg(root)
+            .args(["ls-files", "-z", "--"])
+            .args(chunk.iter().map(|(_, rel)| rel))
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        tracked.extend(split_nul(&output.stdout));
+    }
+
+    Some(tracked)
+}
+
+/// Paths that git's ignore rules exclude
+fn ignored_paths(root: &Path, relative: &[(usize, PathBuf)]) -> Option<Vec<PathBuf>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["check-ignore", "-z", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    {
+        let mut stdin = child.stdin.take()?;
+        for (_, rel) in relative {
+            stdin.write_all(rel.to_string_lossy().as_bytes()).ok()?;
+            stdin.write_all(b"\0").ok()?;
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+
+    // Exit code 1 means "nothing ignored", anything else is an error
+    match output.status.code() {
+        Some(0) => Some(split_nul(&output.stdout)),
+        Some(1) => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+fn split_nul(bytes: &[u8]) -> Vec<PathBuf> {
+    bytes
+        .split(|&b| b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| PathBuf::from(String::from_utf8_lossy(s).into_owned()))
+        .collect()
+}
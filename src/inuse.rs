@@ -0,0 +1,212 @@
+//! # In-Use Module
+//!
+//! Finds artifacts that running processes or active builds still depend on.
+//!
+//! Deleting a `target/` under a running `cargo build` corrupts the build, and
+//! deleting a venv a server runs from pulls the rug out from under it. On
+//! Linux every process's working directory, executable, memory-mapped files
+//! and open file descriptors are read from `/proc`; on every platform Rust
+//! targets are checked for a held `.cargo-lock` build lock.
+
+use crate::types::{Category, DeletableItem};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a process uses a path
+#[derive(Debug, Clone)]
+struct ProcessUse {
+    pid: u32,
+    name: String,
+    how: &'static str,
+}
+
+/// Paths held by running processes at one point in time
+#[derive(Debug, Default)]
+pub struct InUseSnapshot {
+    held: BTreeMap<PathBuf, ProcessUse>,
+}
+
+impl InUseSnapshot {
+    /// Look at what every (visible) process is using right now
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Self {
+        let own_pid = std::process::id();
+        let pids: Vec<u32> = fs::read_dir("/proc")
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                    .filter(|&pid| pid != own_pid)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let uses: Vec<(PathBuf, ProcessUse)> = pids.par_iter().flat_map(|&pid| process_paths(pid)).collect();
+
+        let mut held = BTreeMap::new();
+        for (path, process_use) in uses {
+            held.entry(path).or_insert(process_use);
+        }
+        Self { held }
+    }
+
+    /// Process tables aren't available here; only build locks are checked
+    #[cfg(not(target_os = "linux"))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    /// A process using `path` or anything under it
+    fn held_under(&self, path: &Path) -> Option<&ProcessUse> {
+        // Paths under `path` sort right after it
+        self.held
+            .range(path.to_path_buf()..)
+            .next()
+            .filter(|(held, _)| held.starts_with(path))
+            .map(|(_, process_use)| process_use)
+    }
+
+    /// Why an item is in use, if it is
+    pub fn reason(&self, item: &DeletableItem) -> Option<String> {
+        let path = item.path.canonicalize().ok()?;
+
+        if let Some(process_use) = self.held_under(&path) {
+            return Some(format!(
+                "{} (pid {}, {})",
+                process_use.name, process_use.pid, process_use.how
+            ));
+        }
+
+        (item.category == Category::RustTarget && cargo_build_running(&path)).then(|| "cargo build running".to_string())
+    }
+}
+
+/// Everything one process has open, mapped or sits in
+#[cfg(target_os = "linux")]
+fn process_paths(pid: u32) -> Vec<(PathBuf, ProcessUse)> {
+    let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+    let name = fs::read_to_string(proc_dir.join("comm"))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    let used = |how| ProcessUse {
+        pid,
+        name: name.clone(),
+        how,
+    };
+
+    let mut paths = Vec::new();
+
+    for (link, how) in [("cwd", "working directory"), ("exe", "executable")] {
+        if let Ok(target) = fs::read_link(proc_dir.join(link)) {
+            paths.push((target, used(how)));
+        }
+    }
+
+    if let Ok(maps) = fs::read_to_string(proc_dir.join("maps")) {
+        // address perms offset dev inode pathname
+        for line in maps.lines() {
+            if let Some(path) = line.splitn(6, ' ').nth(5).map(str::trim_start).filter(|p| p.starts_with('/')) {
+                paths.push((PathBuf::from(path), used("mapped file")));
+            }
+        }
+    }
+
+    if let Ok(fds) = fs::read_dir(proc_dir.join("fd")) {
+        for fd in fds.filter_map(|e| e.ok()) {
+            // Sockets, pipes and anonymous inodes aren't paths
+            if let Some(target) = fs::read_link(fd.path()).ok().filter(|t| t.is_absolute()) {
+                paths.push((target, used("open file")));
+            }
+        }
+    }
+
+    paths
+}
+
+/// Check whether cargo holds the build lock of any profile in a target
+///
+/// Cargo keeps `.cargo-lock` in every profile directory (`target/debug`,
+/// `target/<triple>/release`, ...) and holds an exclusive `flock` on it for
+/// the whole build, so a lock we can't share means a build is running.
+fn cargo_build_running(target: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(target) else {
+        return false;
+    };
+
+    let mut profiles: Vec<PathBuf> = Vec::new();
+    for dir in entries.filter_map(|e| e.ok()).filter(|e| e.file_type().is_ok_and(|t| t.is_dir())) {
+        let dir = dir.path();
+        if let Ok(nested) = fs::read_dir(&dir) {
+            profiles.extend(nested.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()));
+        }
+        profiles.push(dir);
+    }
+
+    profiles.iter().any(|profile| lock_is_held(&profile.join(".cargo-lock")))
+}
+
+fn lock_is_held(lock: &Path) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    let Ok(file) = fs::File::open(lock) else {
+        return false;
+    };
+    // A shared lock is released again when `file` is dropped
+    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) != 0 }
+}
+
+/// Flag items that running processes or builds are using
+pub fn annotate_in_use(items: &mut [DeletableItem]) {
+    let snapshot = InUseSnapshot::capture();
+    let reasons: Vec<Option<String>> = items.par_iter().map(|item| snapshot.reason(item)).collect();
+
+    for (item, reason) in items.iter_mut().zip(reasons) {
+        item.in_use = reason;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::io::AsRawFd;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_held_cargo_lock() {
+        let target = std::env::temp_dir().join(format!("gigabroom-inuse-{}", std::process::id()));
+        let profile = target.join("debug");
+        fs::create_dir_all(&profile).unwrap();
+        let lock = fs::File::create(profile.join(".cargo-lock")).unwrap();
+
+        let item = DeletableItem::new(target.clone(), 0, Category::RustTarget, "app".to_string(), SystemTime::now());
+        let snapshot = InUseSnapshot::default();
+        assert_eq!(snapshot.reason(&item), None);
+
+        // flock locks belong to the open file, so a second open sees this one
+        unsafe { libc::flock(lock.as_raw_fd(), libc::LOCK_EX) };
+        assert_eq!(snapshot.reason(&item).as_deref(), Some("cargo build running"));
+
+        drop(lock);
+        let _ = fs::remove_dir_all(&target);
+    }
+
+    #[test]
+    fn test_held_under() {
+        let snapshot = InUseSnapshot {
+            held: BTreeMap::from([(
+                PathBuf::from("/srv/app/.venv/bin/python3"),
+                ProcessUse {
+                    pid: 42,
+                    name: "python3".to_string(),
+                    how: "executable",
+                },
+            )]),
+        };
+
+        assert_eq!(snapshot.held_under(Path::new("/srv/app/.venv")).map(|u| u.pid), Some(42));
+        assert!(snapshot.held_under(Path::new("/srv/app/.venv2")).is_none());
+        assert!(snapshot.held_under(Path::new("/srv/other")).is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! # Journal Module
+//!
+//! Append-only record of everything gigabroom removed, trashed, quarantined
+//! or trimmed, including dry runs and failures.
+//!
+//! Each line of `$XDG_DATA_HOME/gigabroom/journal.jsonl` is one item. Items
+//! written by the same gigabroom process share a run id, which is how
+//! `gigabroom history` groups them.
+
+use crate::quarantine::QuarantinedItem;
+use crate::types::{Category, DeletableItem, DeleteMode};
+use crate::utils::data_dir;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// What was done to a journaled item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Delete,
+    Trash,
+    Quarantine,
+    /// Stale data removed from inside a Rust target
+    Trim,
+    /// Quarantined item deleted by `gigabroom purge`
+    Purge,
+}
+
+impl Action {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Action::Delete => "delete",
+            Action::Trash => "trash",
+            Action::Quarantine => "quarantine",
+            Action::Trim => "trim",
+            Action::Purge => "purge",
+        }
+    }
+
+    /// Returns true if the space is actually freed (not held in trash or quarantine)
+    pub const fn reclaims_space(&self) -> bool {
+        matches!(self, Action::Delete | Action::Trim | Action::Purge)
+    }
+}
+
+impl From<DeleteMode> for Action {
+    fn from(mode: DeleteMode) -> Self {
+        match mode {
+            DeleteMode::Delete => Action::Delete,
+            DeleteMode::Trash => Action::Trash,
+            DeleteMode::Quarantine => Action::Quarantine,
+        }
+    }
+}
+
+/// One line of the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub run: String,
+    pub timestamp: DateTime<Local>,
+    pub path: PathBuf,
+    pub category: Category,
+    /// Bytes removed (or that would have been, for dry runs); for failures,
+    /// whatever was removed before giving up
+    pub size: u64,
+    pub project: String,
+    pub action: Action,
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(item: &DeletableItem, action: Action, dry_run: bool, error: Option<String>) -> Self {
+        Self {
+            run: run_id(),
+            timestamp: Local::now(),
+            path: item.path.clone(),
+            category: item.category,
+            size: item.size,
+            project: item.project_name.clone(),
+            action,
+            dry_run,
+            error,
+        }
+    }
+
+    /// Entry for a quarantined item deleted by `gigabroom purge`
+    pub fn purged(item: &QuarantinedItem, dry_run: bool, error: Option<String>) -> Self {
+        let project = item
+            .original
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            run: run_id(),
+            timestamp: Local::now(),
+            path: item.original.clone(),
+            category: item.category,
+            size: item.size,
+            project,
+            action: Action::Purge,
+            dry_run,
+            error,
+        }
+    }
+}
+
+static RUN_ID: Mutex<Option<String>> = Mutex::new(None);
+
+fn new_run_id() -> String {
+    format!("{}-{}", Local::now().format("%Y%m%d-%H%M%S"), std::process::id())
+}
+
+/// Id shared by every entry written since the process (or [`start_run`]) started
+pub fn run_id() -> String {
+    RUN_ID.lock().unwrap().get_or_insert_with(new_run_id).clone()
+}
+
+/// Journal what follows as a separate run, for processes that clean repeatedly
+pub fn start_run() {
+    *RUN_ID.lock().unwrap() = Some(new_run_id());
+}
+
+/// Returns the path to the journal file
+pub fn journal_path() -> PathBuf {
+    data_dir().join("journal.jsonl")
+}
+
+/// Append entries to the journal
+pub fn record(entries: &[JournalEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let path = journal_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+        lines.push('\n');
+    }
+
+    // One write per batch keeps concurrent runs from interleaving lines
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Every entry in the journal, oldest first
+///
+/// Lines that can't be parsed (a torn write, a future format) are skipped.
+pub fn read_entries() -> Vec<JournalEntry> {
+    let Ok(file) = fs::File::open(journal_path()) else {
+        return Vec::new();
+    };
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// What one gigabroom run did, as reconstructed from its journal entries
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub run: String,
+    pub started: DateTime<Local>,
+    pub actions: Vec<Action>,
+    pub items: usize,
+    pub failed: usize,
+    pub dry_run: bool,
+    /// Bytes removed (or that would have been, for dry runs)
+    pub size: u64,
+}
+
+/// Group entries into runs, in the order the runs started
+pub fn summarize_runs(entries: &[JournalEntry]) -> Vec<RunSummary> {
+    let mut runs: Vec<RunSummary> = Vec::new();
+
+    for entry in entries {
+        let idx = match runs.iter().rposition(|r| r.run == entry.run) {
+            Some(idx) => idx,
+            None => {
+                runs.push(RunSummary {
+                    run: entry.run.clone(),
+                    started: entry.timestamp,
+                    actions: Vec::new(),
+                    items: 0,
+                    failed: 0,
+                    dry_run: true,
+                    size: 0,
+                });
+                runs.len() - 1
+            }
+        };
+        let summary = &mut runs[idx];
+
+        if !summary.actions.contains(&entry.action) {
+            summary.actions.push(entry.action);
+        }
+        summary.items += 1;
+        summary.dry_run &= entry.dry_run;
+        summary.size += entry.size;
+        if entry.error.is_some() {
+            summary.failed += 1;
+        }
+    }
+
+    runs
+}
+
+/// Space reclaimed per category for each period
+///
+/// `period_format` is a chrono format string (e.g. `%Y-%m` for months).
+/// Deletions, trims and purges count, including what partly failed ones
+/// freed; dry runs and items still sitting in the trash or quarantine don't.
+pub fn reclaimed_by_period(
+    entries: &[JournalEntry],
+    period_format: &str,
+) -> BTreeMap<String, BTreeMap<String, u64>> {
+    let mut totals: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+
+    for entry in entries.iter().filter(|e| !e.dry_run && e.action.reclaims_space()) {
+        *totals
+            .entry(entry.timestamp.format(period_format).to_string())
+            .or_default()
+            .entry(entry.category.name().to_string())
+            .or_default() += entry.size;
+    }
+
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_round_trip() {
+        let item = DeletableItem::new(
+            PathBuf::from("/tmp/app/node_modules"),
+            2048,
+            Category::NodeModules,
+            "app".to_string(),
+            std::time::SystemTime::now(),
+        );
+        let entry = JournalEntry::new(&item, Action::Delete, false, Some("busy".to_string()));
+
+        let line = serde_json::to_string(&entry).unwrap();
+        assert!(line.contains("\"action\":\"delete\""));
+
+        let parsed: JournalEntry = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.run, run_id());
+        assert_eq!(parsed.size, 2048);
+    }
+
+    #[test]
+    fn test_runs_and_totals() {
+        let item = |size| {
+            DeletableItem::new(
+                PathBuf::from("/tmp/app/target"),
+                size,
+                Category::RustTarget,
+                "app".to_string(),
+                std::time::SystemTime::now(),
+            )
+        };
+        let mut dry = JournalEntry::new(&item(100), Action::Delete, true, None);
+        dry.run = "earlier".to_string();
+        // Partly deleted before failing
+        let mut failed = JournalEntry::new(&item(500), Action::Delete, false, Some("busy".to_string()));
+        failed.size = 200;
+        let entries = vec![
+            dry,
+            JournalEntry::new(&item(1000), Action::Delete, false, None),
+            failed,
+            JournalEntry::new(&item(300), Action::Quarantine, false, None),
+        ];
+
+        let runs = summarize_runs(&entries);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].dry_run);
+        assert_eq!((runs[1].items, runs[1].failed, runs[1].size), (3, 1, 1500));
+        assert_eq!(runs[1].actions, vec![Action::Delete, Action::Quarantine]);
+
+        // Quarantined items and dry runs don't free space
+        let totals = reclaimed_by_period(&entries, "all");
+        assert_eq!(totals["all"]["Rust target"], 1200);
+    }
+}
@@ -1,162 +1,205 @@
 mod cache;
+mod cargo;
 mod cleaner;
 mod cli;
+mod config;
+mod detectors;
+mod disk;
 mod display;
+mod exclude;
+mod git;
+mod inuse;
+mod journal;
 mod menu;
+mod planner;
+mod project;
+mod quarantine;
+mod remove;
+mod retention;
+mod rules;
 mod scanner;
+mod schedule;
+mod trash;
+mod trim;
 mod types;
 mod ui;
 mod utils;
+mod validate;
+mod watchdog;
 
-use cache::{clear_cache, load_cache, save_cache, show_cache_info};
-use cleaner::{confirm_deletion, delete_items, select_categories, show_interactive_menu};
-use cli::{CacheCommands, Cli, Commands};
+use cache::{clear_cache, forget_items, load_index, save_index, show_cache_info};
+use cleaner::{confirm_deletion, delete_items, drop_kept, drop_protected, select_categories, show_interactive_menu, trim_items};
+use cli::{CacheCommands, Cli, Commands, ConfigCommands, FilterArgs, HistoryPeriod, QuarantineCommands, ScheduleCommands};
 use clap::Parser;
 use colored::*;
 use display::{display_scan_results, print_header};
+use exclude::Excludes;
+use journal::JournalEntry;
 use menu::run_interactive_menu;
-use scanner::{scan_directory, try_indexed_scan};
-use std::path::Path;
-use types::{Category, DeletableItem};
-use utils::{expand_tilde, parse_size};
+use scanner::{resolve_item_ages, scan_directory, try_indexed_scan, INDEX_BACKEND};
+use schedule::{Backend, Frequency, Schedule};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use types::{Category, DeletableItem, DeleteMode, FreePolicy};
+use utils::{expand_tilde, format_size, parse_duration, parse_size};
+use watchdog::{Check, MinFree};
 
-/// Perform a scan with caching logic
+/// Scan every root with caching logic, leaving out what the config excludes
 fn perform_scan(
-    path: &Path,
+    roots: &[PathBuf],
     max_depth: usize,
     force: bool,
     use_index: bool,
+    exclude: &[String],
     quiet: bool,
 ) -> Vec<DeletableItem> {
-    // Use indexing only if explicitly enabled (Spotlight can be unreliable)
-    let should_use_index = use_index;
-
     if force {
         println_unless_quiet!(quiet, "{}", "Forcing fresh scan (cache ignored)...".yellow());
+    }
 
-        let items = if should_use_index {
-            match try_indexed_scan(path, max_depth, quiet) {
-                Ok(items) => {
-                    println_unless_quiet!(quiet, "{}", "✓ Used Spotlight indexing".green());
-                    println_unless_quiet!(quiet, "{}", "  Finds ALL directories (ignores depth limit)".dimmed());
-                    println_unless_quiet!(quiet, "{}", "  Note: May miss very recently created files".dimmed());
-                    items
-                }
-                Err(e) => {
-                    println_unless_quiet!(quiet, "{} {}", "⚠ Spotlight failed:".yellow(), e);
-                    println_unless_quiet!(quiet, "{}", "→ Using filesystem walk (respects depth)...".yellow());
-                    scan_directory(path, max_depth, quiet)
-                }
-            }
-        } else {
-            scan_directory(path, max_depth, quiet)
-        };
-
-        save_cache(path, max_depth, &items);
-        println_unless_quiet!(quiet, "{}", "Scan results cached for future use".dimmed());
-        items
-    } else if let Some(cached_items) = load_cache(path, max_depth) {
-        println_unless_quiet!(
-            quiet,
-            "{}",
-            "Using cached scan results (less than 5 minutes old)".green()
-        );
-        println_unless_quiet!(
-            quiet,
-            "{} {} items\n",
-            "Loaded:".bright_green(),
-            cached_items.len()
+    // --exclude adds to the configured excludes
+    let patterns: Vec<String> = config::get().scan.exclude.iter().chain(exclude).cloned().collect();
+    let mut excludes = Excludes::new(&patterns).unwrap_or_else(|e| {
+        ui::show_error(
+            "Invalid Exclude Pattern",
+            &e,
+            &[
+                "Use glob syntax: *, ?, ** and [abc]",
+                "Example: --exclude '**/Steam' --exclude ~/backups",
+            ],
         );
-        cached_items
-    } else {
-        println_unless_quiet!(quiet, "{}", "Performing fresh scan...".yellow());
-
-        let items = if should_use_index {
-            match try_indexed_scan(path, max_depth, quiet) {
-                Ok(items) => {
-                    println_unless_quiet!(quiet, "{}", "✓ Used Spotlight indexing".green());
-                    println_unless_quiet!(quiet, "{}", "  Finds ALL directories (ignores depth limit)".dimmed());
-                    println_unless_quiet!(quiet, "{}", "  Note: May miss very recently created files".dimmed());
-                    items
-                }
-                Err(e) => {
-                    println_unless_quiet!(quiet, "{} {}", "⚠ Spotlight failed:".yellow(), e);
-                    println_unless_quiet!(quiet, "{}", "→ Using filesystem walk (respects depth)...".yellow());
-                    scan_directory(path, max_depth, quiet)
-                }
-            }
-        } else {
-            scan_directory(path, max_depth, quiet)
-        };
+        std::process::exit(1);
+    });
 
-        save_cache(path, max_depth, &items);
-        println_unless_quiet!(quiet, "{}", "Scan results cached for future use".dimmed());
-        items
+    let mut items: Vec<DeletableItem> = Vec::new();
+    for root in roots {
+        items.extend(scan_root(root, max_depth, force, use_index, &mut excludes, quiet));
     }
+
+    // Overlapping roots find the same items twice
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.path.clone()));
+
+    // Projects may recategorize or keep their artifacts
+    project::apply(&mut items, roots);
+    config::get().retain_enabled(&mut items);
+    items
 }
 
-/// Handle scan command - returns items for potential cleanup
-fn handle_scan(
-    path: String,
+/// Scan one root, from the system index or the scan cache
+fn scan_root(
+    path: &Path,
     max_depth: usize,
     force: bool,
-    index: bool,
-    min_size: Option<String>,
-    _older_than: Option<String>,
-    json: bool,
+    use_index: bool,
+    excludes: &mut Excludes,
     quiet: bool,
-    verbose: bool,
-    from_interactive_menu: bool,
 ) -> Vec<DeletableItem> {
-    let expanded_path = expand_tilde(&path);
-    let scan_path = expanded_path.as_path();
-
-    if !scan_path.exists() {
-        ui::show_error(
-            "Path Not Found",
-            &format!("The specified path does not exist: {}", path),
-            &[
-                "Check if the path is typed correctly",
-                "Use an absolute path (e.g., /Users/name/projects)",
-                "Try using '.' for the current directory",
-            ],
-        );
-        std::process::exit(1);
+    // Use indexing only if explicitly enabled (indexes can be incomplete or stale)
+    if use_index {
+        match try_indexed_scan(path, max_depth, quiet) {
+            Ok(mut items) => {
+                // The index doesn't know about exclusions; drop what a walk would have skipped
+                items.retain(|item| {
+                    let path = std::path::absolute(&item.path).unwrap_or_else(|_| item.path.clone());
+                    !excludes.is_excluded_tree(&path)
+                });
+                println_unless_quiet!(quiet, "{}", format!("✓ Used {} indexing", INDEX_BACKEND).green());
+                println_unless_quiet!(quiet, "{}", "  Finds ALL directories (ignores depth limit)".dimmed());
+                println_unless_quiet!(quiet, "{}", "  Note: May miss very recently created files".dimmed());
+                return items;
+            }
+            Err(e) => {
+                println_unless_quiet!(quiet, "{} {}", format!("⚠ {} failed:", INDEX_BACKEND).yellow(), e);
+                println_unless_quiet!(quiet, "{}", "→ Using filesystem walk (respects depth)...".yellow());
+            }
+        }
     }
 
-    if !scan_path.is_dir() {
-        ui::show_error(
-            "Invalid Path Type",
-            &format!("The path is not a directory: {}", path),
-            &[
-                "Provide a directory path, not a file",
-                "Use the parent directory instead",
-            ],
-        );
-        std::process::exit(1);
-    }
+    // Unchanged directories and artifacts are answered from the scan cache
+    let mut index = load_index();
+    let items = scan_directory(path, max_depth, &mut index, excludes, !force, quiet);
+    save_index(&mut index);
 
-    // Only print header when not in interactive menu (command-line mode)
-    if !from_interactive_menu {
-        print_header(quiet, json);
+    items
+}
+
+/// Roots as shown in "Scanning:" lines
+fn display_roots(roots: &[PathBuf]) -> String {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Directories to work on: the given paths, or the configured roots
+fn resolve_roots(paths: &[String]) -> Vec<PathBuf> {
+    let roots: Vec<PathBuf> = if paths.is_empty() {
+        config::get().roots()
+    } else {
+        paths.iter().map(|path| expand_tilde(path)).collect()
+    };
+
+    for root in &roots {
+        if !root.exists() {
+            ui::show_error(
+                "Path Not Found",
+                &format!("The specified path does not exist: {}", root.display()),
+                &[
+                    "Check if the path is typed correctly",
+                    "Use an absolute path (e.g., /Users/name/projects)",
+                    "Try using '.' for the current directory",
+                ],
+            );
+            std::process::exit(1);
+        }
+
+        if !root.is_dir() {
+            ui::show_error(
+                "Invalid Path Type",
+                &format!("The path is not a directory: {}", root.display()),
+                &[
+                    "Provide a directory path, not a file",
+                    "Use the parent directory instead",
+                ],
+            );
+            std::process::exit(1);
+        }
     }
 
-    if !quiet && !json {
-        println!();
-        print_info!("Scanning", scan_path.display());
-        print_info!("Max depth", max_depth);
-        println!();
+    roots
+}
+
+/// Parse a CLI duration or exit with a formatted error
+fn parse_duration_or_exit(flag: &str, duration_str: &str) -> Duration {
+    match parse_duration(duration_str) {
+        Ok(duration) => duration,
+        Err(e) => {
+            ui::show_error(
+                "Invalid Duration Format",
+                &format!("Could not parse {}: {}", flag, e),
+                &[
+                    "Use format like: 30d, 2w, 6m, 1y (days, weeks, months, years)",
+                    "Examples: --older-than 30d or --newer-than 1w",
+                    "Make sure there's no space between number and unit",
+                ],
+            );
+            std::process::exit(1);
+        }
     }
+}
 
-    let mut items = perform_scan(scan_path, max_depth, force, index, quiet);
+/// Apply size and age filters to scan results from `roots`
+fn apply_filters(items: &mut Vec<DeletableItem>, filters: &FilterArgs, roots: &[PathBuf], quiet: bool) {
+    if !filters.categories.is_empty() {
+        items.retain(|item| filters.categories.contains(&item.category));
+    }
 
     // Apply size filter if specified
-    if let Some(min_size_str) = min_size {
-        match parse_size(&min_size_str) {
+    if let Some(min_size_str) = &filters.min_size {
+        match parse_size(min_size_str) {
             Ok(min_size_bytes) => {
                 items.retain(|item| item.size >= min_size_bytes);
                 println_unless_quiet!(
-                    quiet || json,
+                    quiet,
                     "{} {}",
                     "Filtered by minimum size:".dimmed(),
                     min_size_str
@@ -177,100 +220,221 @@ fn handle_scan(
         }
     }
 
+    // Apply age filters if specified
+    if filters.older_than.is_none() && filters.newer_than.is_none() {
+        return;
+    }
+
+    let older_than = filters
+        .older_than
+        .as_deref()
+        .map(|s| parse_duration_or_exit("--older-than", s));
+    let newer_than = filters
+        .newer_than
+        .as_deref()
+        .map(|s| parse_duration_or_exit("--newer-than", s));
+
+    let now = SystemTime::now();
+    let ages = resolve_item_ages(items, filters.age_source, roots);
+    let mut ages = ages.into_iter();
+
+    items.retain(|_| {
+        let age = ages
+            .next()
+            .and_then(|time| now.duration_since(time).ok())
+            .unwrap_or_default();
+
+        older_than.is_none_or(|min| age >= min) && newer_than.is_none_or(|max| age <= max)
+    });
+
+    if let Some(s) = &filters.older_than {
+        println_unless_quiet!(
+            quiet,
+            "{} {} ({})",
+            "Filtered by age: older than".dimmed(),
+            s,
+            filters.age_source.name()
+        );
+    }
+    if let Some(s) = &filters.newer_than {
+        println_unless_quiet!(
+            quiet,
+            "{} {} ({})",
+            "Filtered by age: newer than".dimmed(),
+            s,
+            filters.age_source.name()
+        );
+    }
+}
+
+/// Handle scan command - returns items for potential cleanup
+#[allow(clippy::too_many_arguments)]
+fn handle_scan(
+    paths: Vec<String>,
+    max_depth: usize,
+    force: bool,
+    index: bool,
+    filters: FilterArgs,
+    json: bool,
+    quiet: bool,
+    verbose: bool,
+    from_interactive_menu: bool,
+) -> Vec<DeletableItem> {
+    let roots = resolve_roots(&paths);
+
+    // Only print header when not in interactive menu (command-line mode)
+    if !from_interactive_menu {
+        print_header(quiet, json);
+    }
+
+    if !quiet && !json {
+        println!();
+        print_info!("Scanning", display_roots(&roots));
+        print_info!("Max depth", max_depth);
+        println!();
+    }
+
+    let mut items = perform_scan(&roots, max_depth, force, index, &filters.exclude, quiet || json);
+    git::annotate_git_state(&mut items);
+    inuse::annotate_in_use(&mut items);
+
+    apply_filters(&mut items, &filters, &roots, quiet || json);
+
     if json {
         println!("{}", serde_json::to_string_pretty(&items).unwrap());
     } else {
         display_scan_results(&items, verbose, quiet, from_interactive_menu);
-        // Statistics and disk space are now integrated into the grouped view
+        if !quiet {
+            disk::show_disk_space(&items);
+        }
+        // Statistics are integrated into the grouped view
     }
 
+    // Kept items are listed, never offered
+    items.retain(|item| item.kept.is_none());
     items
 }
 
 /// Handle clean command
 #[allow(clippy::too_many_arguments)]
 fn handle_clean(
-    path: String,
+    paths: Vec<String>,
     max_depth: usize,
     category: Vec<cli::CategoryFilter>,
     all: bool,
+    preset: Option<String>,
+    policy: bool,
     yes: bool,
     dry_run: bool,
+    include_tracked: bool,
+    force_in_use: bool,
+    free: Option<String>,
+    order: FreePolicy,
+    allow_dangerous: bool,
+    trim: Option<String>,
+    trash: bool,
+    quarantine: bool,
     force: bool,
     index: bool,
-    min_size: Option<String>,
-    _older_than: Option<String>,
+    filters: FilterArgs,
     json: bool,
     quiet: bool,
 ) {
-    let expanded_path = expand_tilde(&path);
-    let clean_path = expanded_path.as_path();
-
-    if !clean_path.exists() {
-        ui::show_error(
-            "Path Not Found",
-            &format!("The specified path does not exist: {}", path),
-            &[
-                "Check if the path is typed correctly",
-                "Use an absolute path (e.g., /Users/name/projects)",
-                "Try using '.' for the current directory",
-            ],
-        );
-        std::process::exit(1);
-    }
+    let preset = preset.as_deref().map(preset_or_exit);
+    let roots = match &preset {
+        Some(preset) => preset_roots(&paths, preset),
+        None => resolve_roots(&paths),
+    };
+    // Command-line filters win over the preset's, which win over the config's
+    let filters = with_config_defaults(match &preset {
+        Some(preset) => with_preset_defaults(filters, preset),
+        None => filters,
+    });
 
-    if !clean_path.is_dir() {
-        ui::show_error(
-            "Invalid Path Type",
-            &format!("The path is not a directory: {}", path),
-            &[
-                "Provide a directory path, not a file",
-                "Use the parent directory instead",
-            ],
-        );
-        std::process::exit(1);
-    }
+    let trim_age = trim.as_deref().map(|age| parse_duration_or_exit("--trim", age));
+    let free_target = free.as_deref().map(|size| match parse_size(size) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            ui::show_error(
+                "Invalid Size Format",
+                &format!("Could not parse --free: {}", e),
+                &[
+                    "Use format like: 100MB, 1GB, 500KB",
+                    "Example: --free 40GB",
+                    "Make sure there's no space between number and unit",
+                ],
+            );
+            std::process::exit(1);
+        }
+    });
+    let delete_mode = if trash {
+        DeleteMode::Trash
+    } else if quarantine {
+        DeleteMode::Quarantine
+    } else {
+        config::get().clean.mode
+    };
 
     print_header(quiet, json);
 
     if !quiet && !json {
-        print_info!("Scanning", clean_path.display());
+        if let Some(preset) = &preset {
+            print_info!("Preset", format!("{} {}", preset.emoji, preset.name));
+        }
+        print_info!("Scanning", display_roots(&roots));
         print_info!("Max depth", max_depth);
         println!();
     }
 
-    let mut all_items = perform_scan(clean_path, max_depth, force, index, quiet || json);
+    let mut all_items = perform_scan(&roots, max_depth, force, index, &filters.exclude, quiet || json);
+    drop_kept(&mut all_items, quiet || json);
+    git::annotate_git_state(&mut all_items);
+    inuse::annotate_in_use(&mut all_items);
 
-    // Apply size filter if specified
-    if let Some(min_size_str) = min_size {
-        match parse_size(&min_size_str) {
-            Ok(min_size_bytes) => {
-                all_items.retain(|item| item.size >= min_size_bytes);
-                println_unless_quiet!(
-                    quiet || json,
-                    "{} {}",
-                    "Filtered by minimum size:".dimmed(),
-                    min_size_str
-                );
-            }
-            Err(e) => {
-                ui::show_error(
-                    "Invalid Size Format",
-                    &format!("Could not parse minimum size: {}", e),
-                    &[
-                        "Use format like: 100MB, 1GB, 500KB",
-                        "Examples: --min-size 100MB or --min-size 1GB",
-                        "Make sure there's no space between number and unit",
-                    ],
-                );
-                std::process::exit(1);
-            }
-        }
-    }
+    apply_filters(&mut all_items, &filters, &roots, quiet || json);
 
     // Determine which categories to clean
-    let selected_categories: Vec<Category> = if all {
-        Category::all().to_vec()
+    // --free picks from every category unless told otherwise
+    let rules = if policy { config::get().retention_rules() } else { Vec::new() };
+    // Projects can set retention rules of their own in .gigabroom.toml
+    let mut projects = project::Projects::within(&roots);
+    let overrides: Vec<_> = if policy {
+        all_items
+            .iter()
+            .map(|item| projects.config_for(&item.path).and_then(|(_, own, _)| own.rule_for(item.category)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if policy && rules.is_empty() && overrides.iter().all(Option::is_none) {
+        ui::show_error(
+            "No Retention Policy",
+            &format!("--policy needs retention rules in {}", config::config_path().display()),
+            &[
+                "Add a [retention] table, e.g.: node = { max_age = \"14d\" }",
+                "Or add one to a project's .gigabroom.toml",
+                "Run `gigabroom config edit` for a commented example",
+            ],
+        );
+        std::process::exit(1);
+    }
+
+    let selected_categories: Vec<Category> = if let Some(preset) = &preset {
+        preset.categories.clone()
+    } else if policy {
+        // --category narrows the policy to some of its categories
+        rules
+            .iter()
+            .chain(overrides.iter().flatten())
+            .map(|rule| rule.category)
+            .filter(|&c| category.is_empty() || category.iter().any(|filter| filter.to_category() == c))
+            .collect()
+    } else if all || (free_target.is_some() && category.is_empty()) {
+        Category::all()
+            .iter()
+            .copied()
+            .chain(rules::custom_categories())
+            .collect()
     } else if !category.is_empty() {
         category.iter().map(|c| c.to_category()).collect()
     } else if yes {
@@ -279,7 +443,7 @@ fn handle_clean(
             "When using --yes (non-interactive mode), you must specify which categories to clean",
             &[
                 "Use --category rust,node,python (comma-separated list)",
-                "Or use --all to clean all categories",
+                "Or use --all to clean all categories, or --preset quick",
                 "Example: rust-cleaner clean --yes --category rust,node",
             ],
         );
@@ -294,11 +458,15 @@ fn handle_clean(
     }
 
     // Filter items by selected categories
-    let filtered_items: Vec<DeletableItem> = all_items
+    let mut filtered_items: Vec<DeletableItem> = all_items
         .into_iter()
         .filter(|item| selected_categories.contains(&item.category))
         .collect();
 
+    if !include_tracked {
+        drop_protected(&mut filtered_items, quiet || json);
+    }
+
     if filtered_items.is_empty() {
         println_unless_quiet!(
             quiet || json,
@@ -308,13 +476,43 @@ fn handle_clean(
         return;
     }
 
+    // With --free, the planner picks the items instead of the user
+    let plan = free_target.map(|target| {
+        planner::plan_free_space(&filtered_items, target, order, allow_dangerous, force_in_use)
+    });
+
+    // With --policy, the retention rules decide
+    let eligible = policy.then(|| {
+        let activity = retention::project_activity(&filtered_items, &roots);
+        let overrides: Vec<_> = filtered_items
+            .iter()
+            .map(|item| projects.config_for(&item.path).and_then(|(_, own, _)| own.rule_for(item.category)))
+            .collect();
+        retention::evaluate(&filtered_items, &activity, &rules, &overrides, SystemTime::now())
+    });
+
     if json {
-        println!("{}", serde_json::to_string_pretty(&filtered_items).unwrap());
+        let planned: Vec<&DeletableItem> = match (&plan, &eligible) {
+            (Some(plan), _) => plan.picks.iter().map(|&i| &filtered_items[i]).collect(),
+            (_, Some(eligible)) => eligible.iter().map(|e| &filtered_items[e.index]).collect(),
+            _ => filtered_items.iter().collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&planned).unwrap());
         return;
     }
 
     // Select items to delete
-    let selections = if yes {
+    let selections = if let Some(plan) = plan {
+        if !quiet {
+            planner::show_plan(&filtered_items, &plan, order);
+        }
+        plan.picks
+    } else if let Some(eligible) = eligible {
+        if !quiet {
+            retention::show_eligible(&filtered_items, &eligible);
+        }
+        eligible.iter().map(|e| e.index).collect()
+    } else if yes {
         (0..filtered_items.len()).collect()
     } else {
         show_interactive_menu(&filtered_items)
@@ -325,59 +523,974 @@ fn handle_clean(
         return;
     }
 
+    // With --trim, Rust targets are trimmed in place instead of deleted
+    let (trim_selections, delete_selections): (Vec<usize>, Vec<usize>) = if trim_age.is_some() {
+        selections
+            .iter()
+            .partition(|&&i| filtered_items[i].category == Category::RustTarget)
+    } else {
+        (Vec::new(), selections)
+    };
+
     // Confirm deletion if not in yes mode
     if !yes && !dry_run && !quiet {
-        let total_size: u64 = selections
+        let total_size: u64 = delete_selections
             .iter()
             .filter_map(|&i| filtered_items.get(i))
             .map(|item| item.size)
             .sum();
 
-        if !confirm_deletion(selections.len(), total_size) {
+        if !trim_selections.is_empty() {
+            println!(
+                "\n{} {} Rust target(s) will be trimmed, not deleted",
+                "ℹ".bright_cyan(),
+                trim_selections.len()
+            );
+        }
+
+        let confirmed = if delete_selections.is_empty() {
+            dialoguer::Confirm::new()
+                .with_prompt("Proceed with trimming?")
+                .default(false)
+                .interact()
+                .unwrap_or(false)
+        } else {
+            // As strict as the preset promised, or the riskiest item, whichever is stricter
+            let safety = ui::SafetyLevel::of_all(delete_selections.iter().map(|&i| filtered_items[i].category))
+                .max(preset.as_ref().map_or(ui::SafetyLevel::Safe, |preset| preset.safety));
+            confirm_deletion(delete_selections.len(), total_size, delete_mode, safety)
+        };
+
+        if !confirmed {
             println!("{}", "Cancelled.".yellow());
             return;
         }
     }
 
-    let items_deleted = delete_items(&filtered_items, &selections, dry_run, quiet);
+    let mut items_deleted = false;
+    if !delete_selections.is_empty() || trim_selections.is_empty() {
+        items_deleted = delete_items(
+            &filtered_items,
+            &delete_selections,
+            &roots,
+            delete_mode,
+            dry_run,
+            force_in_use,
+            quiet,
+        );
+    }
+
+    if let Some(max_age) = trim_age {
+        items_deleted |= trim_items(
+            &filtered_items,
+            &trim_selections,
+            &roots,
+            max_age,
+            dry_run,
+            force_in_use,
+            quiet,
+        );
+    }
 
     if items_deleted {
-        clear_cache();
-        println_unless_quiet!(quiet, "\n{}", "Cache cleared.".dimmed());
+        let touched: Vec<&DeletableItem> = delete_selections
+            .iter()
+            .chain(&trim_selections)
+            .filter_map(|&i| filtered_items.get(i))
+            .collect();
+        forget_items(&touched);
+        println_unless_quiet!(quiet, "\n{}", "Scan cache updated.".dimmed());
+    }
+}
+
+fn handle_restore(list: bool, all: bool, quiet: bool) {
+    let trashed = trash::trashed_items();
+
+    if trashed.is_empty() {
+        println_unless_quiet!(quiet, "{}", "No items trashed by gigabroom are left in the trash.".yellow());
+        return;
+    }
+
+    let labels: Vec<String> = trashed
+        .iter()
+        .map(|item| {
+            format!(
+                "{} {}  {}  {}",
+                item.category.name().bright_white(),
+                item.original.display(),
+                format_size(item.size).bright_yellow(),
+                format!("trashed {}", item.trashed_at.format("%Y-%m-%d %H:%M")).dimmed()
+            )
+        })
+        .collect();
+
+    if list {
+        println!("{}", "Trashed by gigabroom:".bright_cyan().bold());
+        for (i, label) in labels.iter().enumerate() {
+            println!("  {:>3}. {}", i + 1, label);
+        }
+        return;
+    }
+
+    let selections: Vec<usize> = if all {
+        (0..trashed.len()).collect()
+    } else {
+        println!("{}", "Select items to restore (Space to select, Enter to confirm):".bright_cyan());
+        match dialoguer::MultiSelect::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .items(&labels)
+            .interact()
+        {
+            Ok(sel) => sel,
+            Err(_) => {
+                println!("\n{}", "Cancelled".yellow());
+                return;
+            }
+        }
+    };
+
+    let mut restored = 0;
+    for item in selections.iter().filter_map(|&i| trashed.get(i)) {
+        match trash::restore(item) {
+            Ok(()) => {
+                println_unless_quiet!(quiet, "{} {}", "✓".green().bold(), item.original.display());
+                restored += 1;
+            }
+            Err(e) => {
+                print_error!("Failed to restore {}: {}", item.original.display(), e);
+            }
+        }
+    }
+
+    println_unless_quiet!(
+        quiet,
+        "\n{} {} items",
+        "Restored:".bright_green().bold(),
+        restored.to_string().bright_green().bold()
+    );
+}
+
+fn handle_purge(older_than: &str, dry_run: bool, quiet: bool) {
+    let max_age = parse_duration_or_exit("--older-than", older_than);
+    let cutoff = chrono::Local::now() - chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+
+    let expired: Vec<quarantine::QuarantineRun> = quarantine::runs()
+        .into_iter()
+        .filter(|run| run.manifest.created_at < cutoff)
+        .collect();
+
+    if expired.is_empty() {
+        println_unless_quiet!(quiet, "{}", format!("No quarantine runs older than {}.", older_than).yellow());
+        return;
+    }
+
+    let mut purged = 0;
+    let mut freed = 0u64;
+    let mut entries = Vec::new();
+    for run in &expired {
+        if dry_run {
+            println_unless_quiet!(quiet, "Would purge: {} ({})", run.dir.display(), format_size(run.total_size()));
+            purged += 1;
+            freed += run.total_size();
+            entries.extend(run.manifest.items.iter().map(|item| JournalEntry::purged(item, true, None)));
+            continue;
+        }
+
+        let result = quarantine::purge_run(run);
+        entries.extend(
+            run.manifest
+                .items
+                .iter()
+                .map(|item| {
+                    let mut entry = JournalEntry::purged(item, false, result.as_ref().err().cloned());
+                    if entry.error.is_some() {
+                        entry.size = 0;
+                    }
+                    entry
+                }),
+        );
+
+        match result {
+            Ok(()) => {
+                println_unless_quiet!(quiet, "{} {}", "✓".green().bold(), run.dir.display());
+                purged += 1;
+                freed += run.total_size();
+            }
+            Err(e) => {
+                print_error!("Failed to purge {}", e);
+            }
+        }
+    }
+
+    if let Err(e) = journal::record(&entries) {
+        print_error!("Could not write to the journal: {}", e);
+    }
+
+    println_unless_quiet!(
+        quiet,
+        "\n{} {} runs, {}",
+        if dry_run { "Would purge:" } else { "Purged:" }.bright_green().bold(),
+        purged.to_string().bright_green().bold(),
+        format_size(freed).bright_green().bold()
+    );
+}
+
+fn list_quarantine() {
+    let runs = quarantine::runs();
+
+    if runs.is_empty() {
+        println!("{}", "Quarantine is empty.".yellow());
+        return;
+    }
+
+    for run in &runs {
+        println!(
+            "\n{} {}  {}  {}",
+            "▸".bright_cyan(),
+            run.id.bright_white().bold(),
+            format_size(run.total_size()).bright_yellow(),
+            run.dir.display().to_string().dimmed()
+        );
+        for item in &run.manifest.items {
+            println!(
+                "    {} {}  {}",
+                item.category.name().bright_white(),
+                item.original.display(),
+                format_size(item.size).dimmed()
+            );
+        }
+    }
+}
+
+fn restore_quarantined(run_id: Option<&str>, all: bool, quiet: bool) {
+    let mut runs: Vec<quarantine::QuarantineRun> = quarantine::runs()
+        .into_iter()
+        .filter(|run| run_id.is_none_or(|id| run.id == id))
+        .collect();
+
+    if runs.is_empty() {
+        match run_id {
+            Some(id) => print_error!("No quarantine run named {}", id),
+            None => println_unless_quiet!(quiet, "{}", "Quarantine is empty.".yellow()),
+        }
+        return;
+    }
+
+    // (run index, item name) for every quarantined item
+    let entries: Vec<(usize, String)> = runs
+        .iter()
+        .enumerate()
+        .flat_map(|(r, run)| run.manifest.items.iter().map(move |item| (r, item.name.clone())))
+        .collect();
+
+    let selections: Vec<usize> = if all {
+        (0..entries.len()).collect()
+    } else {
+        let labels: Vec<String> = runs
+            .iter()
+            .flat_map(|run| {
+                run.manifest.items.iter().map(|item| {
+                    format!(
+                        "{} {}  {}  {}",
+                        item.category.name().bright_white(),
+                        item.original.display(),
+                        format_size(item.size).bright_yellow(),
+                        run.id.dimmed()
+                    )
+                })
+            })
+            .collect();
+
+        println!("{}", "Select items to restore (Space to select, Enter to confirm):".bright_cyan());
+        match dialoguer::MultiSelect::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .items(&labels)
+            .interact()
+        {
+            Ok(sel) => sel,
+            Err(_) => {
+                println!("\n{}", "Cancelled".yellow());
+                return;
+            }
+        }
+    };
+
+    let mut restored = 0;
+    for (run_idx, name) in selections.iter().filter_map(|&i| entries.get(i)) {
+        let run = &mut runs[*run_idx];
+        let original = run
+            .manifest
+            .items
+            .iter()
+            .find(|item| &item.name == name)
+            .map(|item| item.original.clone())
+            .unwrap_or_default();
+
+        match quarantine::restore(run, name) {
+            Ok(()) => {
+                println_unless_quiet!(quiet, "{} {}", "✓".green().bold(), original.display());
+                restored += 1;
+            }
+            Err(e) => {
+                print_error!("Failed to restore {}: {}", original.display(), e);
+            }
+        }
     }
+
+    println_unless_quiet!(
+        quiet,
+        "\n{} {} items",
+        "Restored:".bright_green().bold(),
+        restored.to_string().bright_green().bold()
+    );
 }
+
+fn handle_history(since: Option<&str>, totals: Option<HistoryPeriod>, limit: usize, json: bool) {
+    let mut entries = journal::read_entries();
+
+    if let Some(since) = since {
+        let max_age = parse_duration_or_exit("--since", since);
+        let cutoff = chrono::Local::now() - chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+        entries.retain(|entry| entry.timestamp >= cutoff);
+    }
+
+    if let Some(period) = totals {
+        let totals = journal::reclaimed_by_period(&entries, period.format());
+
+        if json {
+            match serde_json::to_string_pretty(&totals) {
+                Ok(json) => println!("{}", json),
+                Err(e) => print_error!("Failed to serialize totals: {}", e),
+            }
+            return;
+        }
+
+        if totals.is_empty() {
+            println!("{}", "Nothing reclaimed yet.".yellow());
+            return;
+        }
+
+        for (period, categories) in &totals {
+            let total: u64 = categories.values().sum();
+            println!("\n{} {}  {}", "▸".bright_cyan(), period.bright_white().bold(), format_size(total).bright_green());
+
+            let mut sorted: Vec<_> = categories.iter().collect();
+            sorted.sort_by_key(|(_, size)| std::cmp::Reverse(**size));
+            for (category, size) in sorted {
+                println!("    {:<24} {}", category, format_size(*size).dimmed());
+            }
+        }
+        return;
+    }
+
+    if json {
+        match serde_json::to_string_pretty(&entries) {
+            Ok(json) => println!("{}", json),
+            Err(e) => print_error!("Failed to serialize journal: {}", e),
+        }
+        return;
+    }
+
+    let runs = journal::summarize_runs(&entries);
+    if runs.is_empty() {
+        println!("{}", "The journal is empty.".yellow());
+        return;
+    }
+
+    for run in runs.iter().rev().take(limit) {
+        let actions: Vec<&str> = run.actions.iter().map(|a| a.name()).collect();
+        let mut notes = Vec::new();
+        if run.dry_run {
+            notes.push("dry run".to_string());
+        }
+        if run.failed > 0 {
+            notes.push(format!("{} failed", run.failed).red().to_string());
+        }
+
+        println!(
+            "{} {}  {:<18} {:>6} items  {:>10}  {}",
+            "▸".bright_cyan(),
+            run.started.format("%Y-%m-%d %H:%M").to_string().bright_white().bold(),
+            actions.join(", "),
+            run.items,
+            format_size(run.size),
+            notes.join(", ").dimmed()
+        );
+    }
+
+    if runs.len() > limit {
+        println!("{}", format!("({} older runs not shown, use --limit)", runs.len() - limit).dimmed());
+    }
+}
+
+/// Settings from the config file, exiting with an error if it's malformed
+///
+/// Cleaning with the defaults instead could delete what the file says to
+/// trash or quarantine, or clean what it excludes.
+fn config_or_exit() -> &'static config::Config {
+    config::try_get().unwrap_or_else(|e| {
+        ui::show_error(
+            "Invalid Config",
+            &format!("Could not load {}", e),
+            &[
+                "Check it with: gigabroom config validate",
+                "Fix it, or move it aside to use the defaults",
+            ],
+        );
+        std::process::exit(1);
+    })
+}
+
+/// Look up a clean preset by name, exiting with an error if there's none
+fn preset_or_exit(name: &str) -> ui::CleanPreset {
+    ui::CleanPreset::named(name).unwrap_or_else(|| {
+        ui::show_error(
+            "Unknown Preset",
+            &format!("There is no preset called '{}'", name),
+            &[
+                &format!(
+                    "Use one of: {}",
+                    ui::CleanPreset::all_presets().iter().map(|p| p.key.as_str()).collect::<Vec<_>>().join(", ")
+                ),
+                "Define your own under [preset.<name>] in the config file",
+            ],
+        );
+        std::process::exit(1);
+    })
+}
+
+/// Roots for a preset run: the given paths, else the preset's own roots, else the configured ones
+fn preset_roots(paths: &[String], preset: &ui::CleanPreset) -> Vec<PathBuf> {
+    if paths.is_empty() && !preset.roots.is_empty() {
+        resolve_roots(&preset.roots)
+    } else {
+        resolve_roots(paths)
+    }
+}
+
+/// Check every watched root's filesystem once, cleaning those below the threshold
+fn watchdog_pass(
+    roots: &[PathBuf],
+    min_free: MinFree,
+    preset: &ui::CleanPreset,
+    max_depth: usize,
+    dry_run: bool,
+    quiet: bool,
+) {
+    let stamp = || chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string().dimmed();
+    journal::start_run();
+
+    // Same filters and delete mode as `clean --preset` would use
+    let filters = with_config_defaults(with_preset_defaults(FilterArgs::default(), preset));
+    let mode = config::get().clean.mode;
+
+    for root in roots {
+        // Re-checked for every root, since an earlier one may have cleaned the same filesystem
+        let Some(check) = Check::of(root, min_free) else {
+            print_error!("Could not read free space for {}", root.display());
+            continue;
+        };
+        let free = format!(
+            "{} free ({:.1}%)",
+            format_size(check.usage.available),
+            check.usage.free_percent(check.usage.available)
+        );
+
+        if check.deficit() == 0 {
+            println_unless_quiet!(quiet, "{} {} {} {}", stamp(), "✓".green(), check.mount.display(), free);
+            continue;
+        }
+
+        println_unless_quiet!(
+            quiet,
+            "{} {} {} {}, below {}: freeing {} from {}",
+            stamp(),
+            "⚠".yellow(),
+            check.mount.display(),
+            free,
+            min_free,
+            format_size(check.deficit()).bright_yellow().bold(),
+            root.display()
+        );
+
+        let mut items = perform_scan(std::slice::from_ref(root), max_depth, false, false, &[], true);
+        drop_kept(&mut items, true);
+        git::annotate_git_state(&mut items);
+        inuse::annotate_in_use(&mut items);
+        apply_filters(&mut items, &filters, std::slice::from_ref(root), true);
+        items.retain(|item| {
+            preset.categories.contains(&item.category) && utils::device_of(&item.path) == Some(check.device)
+        });
+        drop_protected(&mut items, quiet);
+
+        let allow_dangerous = preset.safety == ui::SafetyLevel::Dangerous;
+        let plan = planner::plan_free_space(&items, check.deficit(), FreePolicy::Score, allow_dangerous, false);
+        if plan.picks.is_empty() {
+            print_warning!("Nothing under {} that the {} preset may clean", root.display(), preset.name);
+            continue;
+        }
+        if !quiet {
+            planner::show_plan(&items, &plan, FreePolicy::Score);
+        }
+
+        delete_items(&items, &plan.picks, std::slice::from_ref(root), mode, dry_run, false, quiet);
+
+        if dry_run {
+            continue;
+        }
+        if let Some(after) = Check::of(root, min_free).filter(|after| after.deficit() > 0) {
+            print_warning!(
+                "{} is still {} short of {} free",
+                after.mount.display(),
+                format_size(after.deficit()),
+                min_free
+            );
+        }
+    }
+}
+
+fn handle_watchdog(
+    paths: &[String],
+    min_free: &str,
+    policy: &str,
+    interval: Option<&str>,
+    max_depth: usize,
+    dry_run: bool,
+    quiet: bool,
+) {
+    let min_free = MinFree::parse(min_free).unwrap_or_else(|e| {
+        ui::show_error(
+            "Invalid Threshold",
+            &format!("Could not parse --min-free: {}", e),
+            &[
+                "Use a percentage of the filesystem (e.g., 15%)",
+                "Or an absolute size (e.g., 20GB)",
+            ],
+        );
+        std::process::exit(1);
+    });
+
+    let preset = preset_or_exit(policy);
+
+    let interval = interval.map(|i| {
+        watchdog::parse_interval(i).unwrap_or_else(|e| {
+            ui::show_error(
+                "Invalid Interval",
+                &format!("Could not parse --interval: {}", e),
+                &["Use format like: 30s, 15min, 1h, 1d (30s at the least)"],
+            );
+            std::process::exit(1);
+        })
+    });
+
+    let roots = resolve_roots(paths);
+
+    println_unless_quiet!(
+        quiet,
+        "{} keeping {} free with the {} preset{}",
+        "🐕 Watchdog:".bright_cyan().bold(),
+        min_free.to_string().bright_white().bold(),
+        preset.name,
+        match interval {
+            Some(i) => format!(", checking every {}", watchdog::format_interval(i)),
+            None => String::new(),
+        }
+    );
+
+    loop {
+        watchdog_pass(&roots, min_free, &preset, max_depth, dry_run, quiet);
+        match interval {
+            Some(interval) => std::thread::sleep(interval),
+            None => break,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_schedule_install(
+    paths: &[String],
+    frequency: Frequency,
+    preset: &str,
+    older_than: Option<&str>,
+    quarantine: bool,
+    cron: bool,
+    quiet: bool,
+) {
+    let preset = preset_or_exit(preset);
+    if let Some(age) = older_than {
+        parse_duration_or_exit("--older-than", age);
+    }
+
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("gigabroom"));
+
+    let mut commands = Vec::new();
+    for root in preset_roots(paths, &preset) {
+        // The job runs from the home directory, so roots must be absolute
+        let root = root.canonicalize().unwrap_or(root);
+
+        let mut command = vec![
+            exe.display().to_string(),
+            "clean".to_string(),
+            root.display().to_string(),
+            "--yes".to_string(),
+            "--quiet".to_string(),
+        ];
+        command.extend(["--preset".to_string(), preset.key.clone()]);
+        if let Some(age) = older_than {
+            command.extend(["--older-than".to_string(), age.to_string()]);
+        }
+        if quarantine {
+            command.push("--quarantine".to_string());
+        }
+        commands.push(command);
+    }
+
+    let backend = if cron { Backend::Cron } else { Backend::detect() };
+    let schedule = Schedule { frequency, commands };
+
+    match schedule::install(&schedule, backend) {
+        Ok(location) => {
+            println_unless_quiet!(
+                quiet,
+                "{} {} cleanup with the {} preset, as a {} ({})",
+                "✓ Scheduled".green().bold(),
+                frequency.name(),
+                preset.name,
+                backend.name(),
+                location.dimmed()
+            );
+            println_unless_quiet!(
+                quiet,
+                "{}",
+                "  Check it with `gigabroom schedule status`, see what it did with `gigabroom history`".dimmed()
+            );
+        }
+        Err(e) => {
+            print_error!("Could not install the schedule: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_schedule_status() {
+    match schedule::status() {
+        Ok(lines) if lines.is_empty() => {
+            println!("{}", "No cleanup scheduled. Add one with `gigabroom schedule install`.".yellow())
+        }
+        Ok(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        Err(e) => {
+            print_error!("Could not read the schedule: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_schedule_remove(quiet: bool) {
+    match schedule::remove() {
+        Ok(true) => println_unless_quiet!(quiet, "{}", "✓ Scheduled cleanup removed.".green()),
+        Ok(false) => println_unless_quiet!(quiet, "{}", "No cleanup was scheduled.".yellow()),
+        Err(e) => {
+            print_error!("Could not remove the schedule: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Record each path in the `keep` list of the `.gigabroom.toml` governing it
+fn handle_keep(paths: &[String], quiet: bool) {
+    let mut failed = false;
+
+    for path in paths {
+        let expanded = expand_tilde(path);
+        if std::fs::symlink_metadata(&expanded).is_err() {
+            print_error!("No such file or directory: {}", path);
+            failed = true;
+            continue;
+        }
+        let path = project::resolve_path(&expanded);
+        let dir = project::project_dir_for(&path);
+        let Ok(relative) = path.strip_prefix(&dir) else {
+            print_error!("{} is not inside a project", path.display());
+            failed = true;
+            continue;
+        };
+
+        let mut config = if dir.join(project::PROJECT_FILE).exists() {
+            match project::ProjectConfig::load(&dir) {
+                Ok(config) => config,
+                Err(e) => {
+                    print_error!("{}", e);
+                    failed = true;
+                    continue;
+                }
+            }
+        } else {
+            project::ProjectConfig::default()
+        };
+
+        if !config.keep(relative) {
+            println_unless_quiet!(quiet, "{} {}", "Already kept:".dimmed(), path.display());
+            continue;
+        }
+        if let Err(e) = config.save(&dir) {
+            print_error!("Could not save {}", e);
+            failed = true;
+            continue;
+        }
+
+        println_unless_quiet!(
+            quiet,
+            "{} {} {}",
+            "🔒 Keeping".green().bold(),
+            path.display(),
+            format!("(in {})", dir.join(project::PROJECT_FILE).display()).dimmed()
+        );
+        if scanner::is_deletable(&path).is_none() {
+            println_unless_quiet!(quiet, "{}", "  gigabroom doesn't consider it an artifact anyway".dimmed());
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn handle_unkeep(paths: &[String], quiet: bool) {
+    let mut projects = project::Projects::default();
+    let mut failed = false;
+
+    for path in paths {
+        let path = project::resolve_path(&expand_tilde(path));
+
+        let Some((dir, config, relative)) = projects.config_for(&path) else {
+            print_warning!("{} is not kept", path.display());
+            continue;
+        };
+        let mut config = config.clone();
+        if !config.unkeep(&relative) {
+            print_warning!("{} is not kept", path.display());
+            continue;
+        }
+        if let Err(e) = config.save(&dir) {
+            print_error!("Could not save {}", e);
+            failed = true;
+            continue;
+        }
+        // Later paths in the same project must see this edit
+        projects = project::Projects::default();
+
+        println_unless_quiet!(quiet, "{} {}", "🔓 No longer keeping".green().bold(), path.display());
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Fill filters not given on the command line from a preset
+fn with_preset_defaults(filters: FilterArgs, preset: &ui::CleanPreset) -> FilterArgs {
+    FilterArgs {
+        min_size: filters.min_size.or_else(|| preset.min_size.clone()),
+        older_than: filters.older_than.or_else(|| preset.older_than.clone()),
+        ..filters
+    }
+}
+
+/// Fill in filters the command line left out from the config file
+fn with_config_defaults(filters: FilterArgs) -> FilterArgs {
+    FilterArgs {
+        min_size: filters.min_size.or_else(|| config::get().scan.min_size.clone()),
+        ..filters
+    }
+}
+
+fn handle_config_show() {
+    let path = config::config_path();
+    if path.exists() && config::try_get().is_ok() {
+        println!("{} {}\n", "# Settings from".dimmed(), path.display().to_string().dimmed());
+    } else if path.exists() {
+        println!("{} {}\n", "# Defaults, ignoring invalid".dimmed(), path.display().to_string().dimmed());
+    } else {
+        println!("{} {}\n", "# Defaults, no".dimmed(), path.display().to_string().dimmed());
+    }
+    print!("{}", toml::to_string_pretty(config::get()).unwrap_or_default());
+}
+
+fn handle_config_edit() {
+    let path = config::config_path();
+    if !path.exists() {
+        let created = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, config::TEMPLATE));
+        if let Err(e) = created {
+            print_error!("Could not create {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // $EDITOR may carry arguments ("code --wait")
+    let mut words = editor.split_whitespace();
+    let status = std::process::Command::new(words.next().unwrap_or("vi"))
+        .args(words)
+        .arg(&path)
+        .status();
+    if let Err(e) = status {
+        print_error!("Could not run {}: {}", editor, e);
+        std::process::exit(1);
+    }
+
+    handle_config_validate();
+}
+
+fn handle_config_validate() {
+    let path = config::config_path();
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        println!("{} {} {}", "✓".green(), path.display(), "doesn't exist, using defaults".dimmed());
+        return;
+    };
+
+    let config: config::Config = match toml::from_str(&contents) {
+        Ok(config) => config,
+        Err(e) => {
+            print_error!("{}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let (errors, warnings) = config.check();
+    for warning in &warnings {
+        print_warning!("{}", warning);
+    }
+    for error in &errors {
+        print_error!("{}", error);
+    }
+
+    if errors.is_empty() {
+        println!("{} {} is valid", "✓".green(), path.display());
+    } else {
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
         Some(Commands::Scan {
-            path,
+            paths,
             max_depth,
             force,
             index,
-            min_size,
-            older_than,
+            filters,
             json,
         }) => {
-            handle_scan(path, max_depth, force, index, min_size, older_than, json, cli.quiet, cli.verbose, false);
+            let max_depth = max_depth.unwrap_or(config::get().scan.max_depth);
+            let filters = with_config_defaults(filters);
+            handle_scan(paths, max_depth, force, index, filters, json, cli.quiet, cli.verbose, false);
         }
 
         Some(Commands::Clean {
-            path,
+            paths,
             max_depth,
             category,
             all,
+            preset,
+            policy,
             yes,
             dry_run,
+            include_tracked,
+            force_in_use,
+            free,
+            order,
+            allow_dangerous,
+            trim,
+            trash,
+            quarantine,
             force,
             index,
-            min_size,
-            older_than,
+            filters,
             json,
         }) => handle_clean(
-            path, max_depth, category, all, yes, dry_run, force, index, min_size, older_than, json, cli.quiet,
+            paths,
+            max_depth.unwrap_or(config_or_exit().scan.max_depth),
+            category, all, preset, policy, yes, dry_run, include_tracked, force_in_use, free, order,
+            allow_dangerous, trim, trash, quarantine, force, index, filters, json, cli.quiet,
+        ),
+
+        Some(Commands::Restore { list, all }) => handle_restore(list, all, cli.quiet),
+
+        Some(Commands::Purge { older_than, dry_run }) => handle_purge(&older_than, dry_run, cli.quiet),
+
+        Some(Commands::Quarantine { action }) => match action {
+            QuarantineCommands::List => list_quarantine(),
+            QuarantineCommands::Restore { run, all } => restore_quarantined(run.as_deref(), all, cli.quiet),
+        },
+
+        Some(Commands::History {
+            since,
+            totals,
+            limit,
+            json,
+        }) => handle_history(since.as_deref(), totals, limit, json),
+
+        Some(Commands::Watchdog {
+            paths,
+            min_free,
+            policy,
+            interval,
+            max_depth,
+            dry_run,
+        }) => handle_watchdog(
+            &paths,
+            &min_free,
+            &policy,
+            interval.as_deref(),
+            max_depth.unwrap_or(config_or_exit().scan.max_depth),
+            dry_run,
+            cli.quiet,
         ),
 
+        Some(Commands::Schedule { action }) => match action {
+            ScheduleCommands::Install {
+                paths,
+                hourly,
+                daily: _,
+                weekly,
+                preset,
+                older_than,
+                quarantine,
+                cron,
+            } => {
+                let frequency = if hourly {
+                    Frequency::Hourly
+                } else if weekly {
+                    Frequency::Weekly
+                } else {
+                    Frequency::Daily
+                };
+                handle_schedule_install(&paths, frequency, &preset, older_than.as_deref(), quarantine, cron, cli.quiet)
+            }
+            ScheduleCommands::Status => handle_schedule_status(),
+            ScheduleCommands::Remove => handle_schedule_remove(cli.quiet),
+        },
+
+        Some(Commands::Keep { paths }) => handle_keep(&paths, cli.quiet),
+
+        Some(Commands::Unkeep { paths }) => handle_unkeep(&paths, cli.quiet),
+
+        Some(Commands::Config { action }) => match action {
+            ConfigCommands::Show => handle_config_show(),
+            ConfigCommands::Edit => handle_config_edit(),
+            ConfigCommands::Validate => handle_config_validate(),
+        },
+
         Some(Commands::Cache { action }) => match action {
             CacheCommands::Clear => {
                 clear_cache();
@@ -389,6 +1502,7 @@ fn main() {
         },
 
         None => {
+            config_or_exit();
             print_header(cli.quiet, false);
             if !cli.quiet {
                 run_interactive_menu(handle_scan);
@@ -1,16 +1,20 @@
 /// Interactive menu system
-use crate::cache::{clear_cache, show_cache_info};
-use crate::cleaner::{confirm_deletion, delete_items, show_interactive_menu};
+use crate::cache::{clear_cache, forget_items, show_cache_info};
+use crate::cleaner::{confirm_deletion, delete_items, drop_protected, show_interactive_menu};
+use crate::cli::FilterArgs;
+use crate::config;
 use crate::display::print_header;
-use crate::types::DeletableItem;
+use crate::scanner::INDEX_BACKEND;
+use crate::types::{DeletableItem, DeleteMode};
 use crate::utils::{expand_tilde, format_size};
 use crate::ui;
 use colored::*;
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use std::path::PathBuf;
 
 /// Run the main interactive menu loop
 pub fn run_interactive_menu(
-    handle_scan_fn: impl Fn(String, usize, bool, bool, Option<String>, Option<String>, bool, bool, bool, bool) -> Vec<DeletableItem>
+    handle_scan_fn: impl Fn(Vec<String>, usize, bool, bool, FilterArgs, bool, bool, bool, bool) -> Vec<DeletableItem>
 ) {
     loop {
         ui::clear_screen();
@@ -20,6 +24,7 @@ pub fn run_interactive_menu(
 
         let options = vec![
             "📊 Scan & Clean",
+            "🎯 Presets",
             "💾 Cache Management",
             "❓ Help & Keyboard Shortcuts",
             "❌ Exit",
@@ -41,9 +46,10 @@ pub fn run_interactive_menu(
 
         match selection {
             0 => menu_scan(&handle_scan_fn),
-            1 => menu_cache(),
-            2 => menu_help(),
-            3 => {
+            1 => menu_presets(&handle_scan_fn),
+            2 => menu_cache(),
+            3 => menu_help(),
+            4 => {
                 println!("\n{}", "Goodbye!".bright_green());
                 break;
             }
@@ -55,22 +61,29 @@ pub fn run_interactive_menu(
 /// Interactive scan menu
 fn menu_scan<F>(handle_scan_fn: &F)
 where
-    F: Fn(String, usize, bool, bool, Option<String>, Option<String>, bool, bool, bool, bool) -> Vec<DeletableItem>
+    F: Fn(Vec<String>, usize, bool, bool, FilterArgs, bool, bool, bool, bool) -> Vec<DeletableItem>
 {
     ui::clear_screen();
     ui::show_breadcrumb(&["Main Menu", "Scan & Clean"]);
     println!("\n{}", "Scan & Clean Build Artifacts".bright_cyan().bold());
 
+    let config = config::get();
+    let default_roots = if config.scan.roots.is_empty() {
+        ".".to_string()
+    } else {
+        config.scan.roots.join(", ")
+    };
+
     let path: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Directory to scan")
-        .default(".".to_string())
+        .with_prompt("Directories to scan (comma-separated)")
+        .default(default_roots.clone())
         .interact_text()
-        .unwrap_or_else(|_| ".".to_string());
+        .unwrap_or(default_roots);
 
     let max_depth: usize = loop {
         match Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Maximum depth")
-            .default("10".to_string())
+            .default(config.scan.max_depth.to_string())
             .interact_text()
         {
             Ok(input) => {
@@ -86,16 +99,17 @@ where
                     }
                 }
             }
-            Err(_) => break 10,
+            Err(_) => break config.scan.max_depth,
         }
     };
 
-    let use_index = if cfg!(target_os = "macos") {
+    let use_index = if cfg!(any(target_os = "macos", target_os = "linux")) {
         Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Use Spotlight? (fast, finds ALL dirs, ignores depth)")
-            .default(true)
+            .with_prompt(format!("Use {}? (fast, finds ALL dirs, ignores depth)", INDEX_BACKEND))
+            // locate databases are only refreshed daily, so don't default to them
+            .default(cfg!(target_os = "macos"))
             .interact()
-            .unwrap_or(true)
+            .unwrap_or(false)
     } else {
         false
     };
@@ -108,6 +122,7 @@ where
 
     let min_size_input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt("Minimum size filter (e.g., '100MB', '1GB', or press Enter to skip)")
+        .with_initial_text(config.scan.min_size.clone().unwrap_or_default())
         .allow_empty(true)
         .interact_text()
         .unwrap_or_default();
@@ -126,11 +141,18 @@ where
 
     println!();
 
-    // Expand tilde in path
-    let expanded_path = expand_tilde(&path);
-    let path_str = expanded_path.to_string_lossy().to_string();
+    let roots = parse_roots(&path);
+    let root_strs: Vec<String> = roots.iter().map(|root| root.to_string_lossy().to_string()).collect();
+
+    let filters = FilterArgs {
+        min_size,
+        ..FilterArgs::default()
+    };
 
-    let items = handle_scan_fn(path_str, max_depth, force, use_index, min_size, None, false, false, verbose, true);
+    let mut items = handle_scan_fn(root_strs, max_depth, force, use_index, filters, false, false, verbose, true);
+
+    // Never offer items holding committed files from the menu
+    drop_protected(&mut items, false);
 
     // If no items found, show message and wait
     if items.is_empty() {
@@ -192,48 +214,177 @@ where
             };
 
             if !selections.is_empty() {
-                // Ask for dry-run
-                let dry_run = match Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Dry-run mode (preview only)?")
-                    .default(false)
-                    .interact()
-                {
-                    Ok(result) => result,
-                    Err(_) => {
-                        // ESC pressed - return to main menu
-                        return;
-                    }
-                };
+                let safety = ui::SafetyLevel::of_all(selections.iter().map(|&i| items[i].category));
+                if !remove_selected(&items, &selections, &roots, safety) {
+                    return;
+                }
+            }
+        }
+    }
 
-                // Confirm deletion if not in dry-run mode
-                if !dry_run {
-                    let total_size: u64 = selections
-                        .iter()
-                        .filter_map(|&i| items.get(i))
-                        .map(|item| item.size)
-                        .sum();
+    // Wait before returning to main menu
+    println!("\n{}", "[Press Enter or ESC to return to main menu]".dimmed());
+    let _ = Input::<String>::new().allow_empty(true).interact();
+}
 
-                    if !confirm_deletion(selections.len(), total_size) {
-                        println!("{}", "Cancelled.".yellow());
-                        return;
-                    }
-                }
+/// Ask how to remove the selected items, confirm as strictly as `safety`
+/// calls for, then remove them. Returns false if the user backed out.
+fn remove_selected(items: &[DeletableItem], selections: &[usize], roots: &[PathBuf], safety: ui::SafetyLevel) -> bool {
+    let dry_run = match Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Dry-run mode (preview only)?")
+        .default(false)
+        .interact()
+    {
+        Ok(result) => result,
+        Err(_) => {
+            // ESC pressed - return to main menu
+            return false;
+        }
+    };
 
-                let items_deleted = delete_items(&items, &selections, dry_run, false);
+    let mode = if dry_run {
+        DeleteMode::Delete
+    } else {
+        let modes = [
+            "🗑️  Delete permanently",
+            "♻️  Move to trash (undo with `gigabroom restore`)",
+            "📦 Quarantine (undo with `gigabroom quarantine restore`)",
+        ];
+        let default_mode = match config::get().clean.mode {
+            DeleteMode::Delete => 0,
+            DeleteMode::Trash => 1,
+            DeleteMode::Quarantine => 2,
+        };
+        match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("How should the items be removed?")
+            .items(&modes)
+            .default(default_mode)
+            .interact()
+        {
+            Ok(1) => DeleteMode::Trash,
+            Ok(2) => DeleteMode::Quarantine,
+            Ok(_) => DeleteMode::Delete,
+            Err(_) => return false,
+        }
+    };
 
-                if items_deleted {
-                    clear_cache();
-                    println!("\n{}", "Cache cleared.".dimmed());
-                }
+    // Confirm deletion if not in dry-run mode
+    if !dry_run {
+        let total_size: u64 = selections
+            .iter()
+            .filter_map(|&i| items.get(i))
+            .map(|item| item.size)
+            .sum();
+
+        if !confirm_deletion(selections.len(), total_size, mode, safety) {
+            println!("{}", "Cancelled.".yellow());
+            return false;
+        }
+    }
+
+    let items_deleted = delete_items(items, selections, roots, mode, dry_run, false, false);
+
+    if items_deleted {
+        let deleted: Vec<&DeletableItem> = selections.iter().filter_map(|&i| items.get(i)).collect();
+        forget_items(&deleted);
+        println!("\n{}", "Scan cache updated.".dimmed());
+    }
+    true
+}
+
+/// Interactive presets menu: pick a preset, scan with it, clean
+fn menu_presets<F>(handle_scan_fn: &F)
+where
+    F: Fn(Vec<String>, usize, bool, bool, FilterArgs, bool, bool, bool, bool) -> Vec<DeletableItem>
+{
+    ui::clear_screen();
+    ui::show_breadcrumb(&["Main Menu", "Presets"]);
+    println!("\n{}", "Clean with a Preset".bright_cyan().bold());
+
+    let presets = ui::CleanPreset::all_presets();
+    let names: Vec<String> = presets.iter().map(|preset| preset.display_name()).collect();
+
+    let Ok(choice) = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a preset")
+        .items(&names)
+        .default(0)
+        .interact()
+    else {
+        return;
+    };
+    let preset = &presets[choice];
+
+    let config = config::get();
+    let default_roots = if !preset.roots.is_empty() {
+        preset.roots.join(", ")
+    } else if !config.scan.roots.is_empty() {
+        config.scan.roots.join(", ")
+    } else {
+        ".".to_string()
+    };
+
+    let path: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Directories to clean (comma-separated)")
+        .default(default_roots.clone())
+        .interact_text()
+        .unwrap_or(default_roots);
+
+    println!();
+
+    let roots = parse_roots(&path);
+    let root_strs: Vec<String> = roots.iter().map(|root| root.to_string_lossy().to_string()).collect();
+
+    let filters = FilterArgs {
+        min_size: preset.min_size.clone().or_else(|| config.scan.min_size.clone()),
+        older_than: preset.older_than.clone(),
+        categories: preset.categories.clone(),
+        ..FilterArgs::default()
+    };
+
+    let mut items = handle_scan_fn(root_strs, config.scan.max_depth, false, false, filters, false, false, false, true);
+    drop_protected(&mut items, false);
+
+    if !items.is_empty() {
+        let total_size: u64 = items.iter().map(|item| item.size).sum();
+        let options = [
+            format!("🧹 Clean all {} items ({})", items.len(), format_size(total_size)),
+            "☑️  Pick items".to_string(),
+            "↩️  Back to Main Menu".to_string(),
+        ];
+
+        let selections: Vec<usize> = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("{} {}", preset.emoji, preset.name))
+            .items(&options)
+            .default(0)
+            .interact()
+        {
+            Ok(0) => (0..items.len()).collect(),
+            Ok(1) => show_interactive_menu(&items),
+            _ => return,
+        };
+
+        if !selections.is_empty() {
+            let safety = ui::SafetyLevel::of_all(selections.iter().map(|&i| items[i].category)).max(preset.safety);
+            if !remove_selected(&items, &selections, &roots, safety) {
+                return;
             }
         }
     }
 
-    // Wait before returning to main menu
     println!("\n{}", "[Press Enter or ESC to return to main menu]".dimmed());
     let _ = Input::<String>::new().allow_empty(true).interact();
 }
 
+/// Split a comma-separated list of directories from a prompt
+fn parse_roots(input: &str) -> Vec<PathBuf> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|root| !root.is_empty())
+        .map(expand_tilde)
+        .collect()
+}
+
 /// Interactive cache menu
 fn menu_cache() {
     ui::clear_screen();
@@ -296,24 +447,26 @@ fn menu_help() {
 
     println!("\n{}", "COMMAND-LINE USAGE:".bright_cyan().bold());
     println!("{}", "──────────────────────────────────────────────────────".bright_black());
-    println!("  {:<30} {}", "gigabroom".bright_green(), "Launch interactive menu");
-    println!("  {:<30} {}", "gigabroom scan [PATH]".bright_green(), "Scan for build artifacts");
-    println!("  {:<30} {}", "gigabroom clean [PATH]".bright_green(), "Clean with interactive selection");
-    println!("  {:<30} {}", "gigabroom --help".bright_green(), "Show detailed help");
+    println!("  {:<30} Launch interactive menu", "gigabroom".bright_green());
+    println!("  {:<30} Scan for build artifacts", "gigabroom scan [PATH]".bright_green());
+    println!("  {:<30} Clean with interactive selection", "gigabroom clean [PATH]".bright_green());
+    println!("  {:<30} Show detailed help", "gigabroom --help".bright_green());
 
     println!("\n{}", "TIPS & TRICKS:".bright_cyan().bold());
     println!("{}", "──────────────────────────────────────────────────────".bright_black());
     println!("  {} Use presets for one-click cleaning", "•".bright_yellow());
-    println!("  {} Spotlight search (macOS) is faster than recursive", "•".bright_yellow());
+    println!("  {} Spotlight (macOS) or locate (Linux) is faster than recursive", "•".bright_yellow());
     println!("  {} Check disk space before/after with --verbose", "•".bright_yellow());
     println!("  {} Use --dry-run to preview without deleting", "•".bright_yellow());
     println!("  {} Filter by size: --min-size 100MB", "•".bright_yellow());
 
     println!("\n{}", "PRESETS:".bright_cyan().bold());
     println!("{}", "──────────────────────────────────────────────────────".bright_black());
-    println!("  {} {} Safe, common build artifacts", "🚀 Quick Clean:".bright_green().bold(), "-");
-    println!("  {} {} All build artifacts + caches", "🧹 Deep Clean:".bright_yellow().bold(), "-");
-    println!("  {} {} Includes global package caches", "⚠️  Nuclear Clean:".bright_red().bold(), "-");
+    println!("  {} - Safe, common build artifacts", "🚀 Quick Clean:".bright_green().bold());
+    println!("  {} - All build artifacts + caches", "🧹 Deep Clean:".bright_yellow().bold());
+    println!("  {} - Includes global package caches", "⚠️  Nuclear Clean:".bright_red().bold());
+    println!("  {} Add your own under [preset.<name>] in the config file", "•".bright_yellow());
+    println!("  {} gigabroom clean --preset quick", "•".bright_yellow());
 
     println!("\n{}", "[Press Enter or ESC to return]".dimmed());
     let _ = Input::<String>::new().allow_empty(true).interact();
@@ -0,0 +1,185 @@
+//! # Planner Module
+//!
+//! Picks items to delete for `clean --free`, which asks for an amount of
+//! space rather than a list of categories.
+//!
+//! Items are taken from the safest category level first (see
+//! [`SafetyLevel::of`]), ordered within each level by the chosen
+//! [`FreePolicy`], until the target is met.
+
+use crate::types::{DeletableItem, FreePolicy};
+use crate::ui::SafetyLevel;
+use crate::utils::format_size;
+use colored::*;
+use std::cmp::Reverse;
+use std::time::{Duration, SystemTime};
+
+/// Items picked to free a target amount of space
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    /// Indices into the scanned items, in the order they were picked
+    pub picks: Vec<usize>,
+    pub total: u64,
+    pub target: u64,
+}
+
+impl Plan {
+    pub fn reaches_target(&self) -> bool {
+        self.total >= self.target
+    }
+}
+
+fn age_days(item: &DeletableItem, now: SystemTime) -> f64 {
+    now.duration_since(item.last_modified).unwrap_or(Duration::ZERO).as_secs_f64() / 86400.0
+}
+
+/// Old and big items score higher; doubling either adds about the same
+fn score(item: &DeletableItem, now: SystemTime) -> f64 {
+    let mib = item.size as f64 / (1024.0 * 1024.0);
+    (1.0 + mib).log2() * (2.0 + age_days(item, now)).log2()
+}
+
+/// Pick items until `target` bytes would be freed
+///
+/// Dangerous categories are only considered with `allow_dangerous`, and items
+/// in use only with `include_in_use`. If everything eligible isn't enough,
+/// the plan holds all of it.
+pub fn plan_free_space(
+    items: &[DeletableItem],
+    target: u64,
+    policy: FreePolicy,
+    allow_dangerous: bool,
+    include_in_use: bool,
+) -> Plan {
+    let now = SystemTime::now();
+
+    let mut candidates: Vec<(usize, SafetyLevel)> = items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| (idx, SafetyLevel::of(item.category)))
+        .filter(|(_, level)| allow_dangerous || *level != SafetyLevel::Dangerous)
+        .filter(|(idx, _)| include_in_use || items[*idx].in_use.is_none())
+        .filter(|(idx, _)| items[*idx].size > 0)
+        .collect();
+
+    match policy {
+        FreePolicy::Oldest => candidates.sort_by_key(|&(idx, level)| (level, items[idx].last_modified)),
+        FreePolicy::Largest => candidates.sort_by_key(|&(idx, level)| (level, Reverse(items[idx].size))),
+        FreePolicy::Score => candidates.sort_by(|&(a, level_a), &(b, level_b)| {
+            level_a
+                .cmp(&level_b)
+                .then_with(|| score(&items[b], now).total_cmp(&score(&items[a], now)))
+        }),
+    }
+
+    let mut plan = Plan {
+        target,
+        ..Plan::default()
+    };
+
+    for (idx, _) in candidates {
+        if plan.reaches_target() {
+            break;
+        }
+        plan.picks.push(idx);
+        plan.total += items[idx].size;
+    }
+
+    plan
+}
+
+/// Show what a plan would delete, grouped by safety level
+pub fn show_plan(items: &[DeletableItem], plan: &Plan, policy: FreePolicy) {
+    let now = SystemTime::now();
+
+    println!(
+        "\n{} {} {}",
+        "📐 Plan to free".bright_cyan().bold(),
+        format_size(plan.target).bright_cyan().bold(),
+        format!("(safest categories first, then {})", policy.name()).dimmed()
+    );
+
+    let mut level = None;
+    let mut running = 0u64;
+    for (n, &idx) in plan.picks.iter().enumerate() {
+        let item = &items[idx];
+        let item_level = SafetyLevel::of(item.category);
+        if level != Some(item_level) {
+            level = Some(item_level);
+            let label = match item_level {
+                SafetyLevel::Safe => item_level.label().green(),
+                SafetyLevel::Moderate => item_level.label().yellow(),
+                SafetyLevel::Dangerous => item_level.label().red(),
+            };
+            println!("  {}", label.bold());
+        }
+
+        running += item.size;
+        println!(
+            "  {:3}. {:16} {:24} {:>10}  {:>5}  {}",
+            n + 1,
+            item.category.name(),
+            item.project_name.chars().take(24).collect::<String>().bright_cyan(),
+            format_size(item.size),
+            format!("{:.0}d", age_days(item, now)),
+            format!("→ {}", format_size(running)).dimmed()
+        );
+    }
+
+    println!(
+        "{} {} items, {}",
+        "Planned:".bright_white().bold(),
+        plan.picks.len(),
+        format_size(plan.total).bright_green().bold()
+    );
+
+    if !plan.reaches_target() {
+        crate::print_warning!(
+            "Only {} can be freed from eligible items ({} short of the target)",
+            format_size(plan.total),
+            format_size(plan.target - plan.total)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Category;
+    use std::path::PathBuf;
+
+    fn item(category: Category, size: u64, days_old: u64) -> DeletableItem {
+        DeletableItem::new(
+            PathBuf::from(format!("/tmp/{}-{}", size, days_old)),
+            size,
+            category,
+            "app".to_string(),
+            SystemTime::now() - Duration::from_secs(days_old * 86400),
+        )
+    }
+
+    #[test]
+    fn test_plan_prefers_safe_and_stops_at_target() {
+        let items = vec![
+            item(Category::GoVendor, 500, 100),
+            item(Category::RustTarget, 100, 1),
+            item(Category::RustTarget, 300, 50),
+            item(Category::PackageCache, 1000, 365),
+        ];
+
+        let plan = plan_free_space(&items, 350, FreePolicy::Largest, false, false);
+        assert_eq!(plan.picks, vec![2, 1]);
+        assert!(plan.reaches_target());
+
+        let plan = plan_free_space(&items, 350, FreePolicy::Oldest, false, false);
+        assert_eq!(plan.picks, vec![2, 1]);
+
+        // Dangerous categories stay out unless allowed
+        let plan = plan_free_space(&items, 10_000, FreePolicy::Score, false, false);
+        assert_eq!(plan.total, 900);
+        assert!(!plan.reaches_target());
+
+        let plan = plan_free_space(&items, 10_000, FreePolicy::Score, true, false);
+        assert_eq!(plan.picks.last(), Some(&3));
+    }
+}
@@ -0,0 +1,283 @@
+//! # Project Module
+//!
+//! Per-project overrides from a `.gigabroom.toml` in the project root.
+//!
+//! ```toml
+//! # Artifacts gigabroom lists but never offers or deletes, relative to this file
+//! keep = ["build", ".venv"]
+//!
+//! # Artifacts to treat as another category
+//! [category]
+//! "out" = "temp"
+//!
+//! # Retention for this project, replacing or adding to the config file's rules
+//! [retention]
+//! node = { max_age = "60d" }
+//! ```
+//!
+//! An item is governed by the nearest `.gigabroom.toml` above it, looking no
+//! higher than the scan root and never in the home directory or `/`.
+//! `gigabroom keep` and `unkeep` edit the `keep` list.
+
+use crate::config::category_named;
+use crate::retention::{RetentionRule, Rule};
+use crate::scanner::find_project_root;
+use crate::types::{Category, DeletableItem};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Per-project settings file
+pub const PROJECT_FILE: &str = ".gigabroom.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Artifacts never offered for cleaning, relative to the project root
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keep: Vec<String>,
+    /// Category overrides, by artifact path relative to the project root
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub category: BTreeMap<String, String>,
+    /// Retention rules replacing or adding to the config file's for this project
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub retention: BTreeMap<String, RetentionRule>,
+}
+
+/// Normalize a `keep` or `category` entry so "./build/" and "build" compare equal
+fn normalize(entry: &str) -> PathBuf {
+    Path::new(entry.trim())
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// Make `path` absolute with symlinks above it resolved
+///
+/// Scans, `keep` and `unkeep` all look paths up through this, so an artifact
+/// reached through a symlinked directory finds the same project file entry.
+/// The artifact itself may be a symlink or already gone, so it's left as is.
+pub fn resolve_path(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent.canonicalize().map(|parent| parent.join(name)),
+        _ => path.canonicalize(),
+    };
+    resolved.unwrap_or(path)
+}
+
+impl ProjectConfig {
+    /// Parse a project file and check its values
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(contents).map_err(|e| e.to_string())?;
+
+        for (path, name) in &config.category {
+            if category_named(name).is_none() {
+                return Err(format!("category.\"{}\": unknown category '{}'", path, name));
+            }
+        }
+        for (name, rule) in &config.retention {
+            let category = category_named(name).ok_or_else(|| format!("retention: unknown category '{}'", name))?;
+            if rule.keep_recent.is_some() {
+                return Err(format!("retention.{}: keep_recent only applies in the user config", name));
+            }
+            Rule::parse(name, category, rule).map_err(|e| format!("retention.{}: {}", name, e))?;
+        }
+
+        Ok(config)
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(PROJECT_FILE);
+        let contents = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Write the file, or remove it once there's nothing left in it
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        let path = dir.join(PROJECT_FILE);
+        let result = if *self == Self::default() {
+            fs::remove_file(&path)
+        } else {
+            let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+            fs::write(&path, contents)
+        };
+        result.map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Whether the artifact at `relative` (to the project root) is kept
+    pub fn keeps(&self, relative: &Path) -> bool {
+        self.keep.iter().any(|entry| normalize(entry) == relative)
+    }
+
+    /// Add a keep entry; false if the artifact was already kept
+    pub fn keep(&mut self, relative: &Path) -> bool {
+        if self.keeps(relative) {
+            return false;
+        }
+        self.keep.push(relative.to_string_lossy().into_owned());
+        true
+    }
+
+    /// Remove the artifact's keep entries; false if there were none
+    pub fn unkeep(&mut self, relative: &Path) -> bool {
+        let before = self.keep.len();
+        self.keep.retain(|entry| normalize(entry) != relative);
+        self.keep.len() < before
+    }
+
+    pub fn category_of(&self, relative: &Path) -> Option<Category> {
+        self.category
+            .iter()
+            .find(|(entry, _)| normalize(entry) == relative)
+            .and_then(|(_, name)| category_named(name))
+    }
+
+    /// This project's retention rule for a category, if it sets one
+    pub fn rule_for(&self, category: Category) -> Option<Rule> {
+        self.retention.iter().find_map(|(name, rule)| {
+            let named = category_named(name).filter(|&c| c == category)?;
+            Rule::parse(&format!("{} ({})", name, PROJECT_FILE), named, rule).ok()
+        })
+    }
+}
+
+/// Project files found above scanned items, each read once
+pub struct Projects {
+    /// Directory → the directory of the nearest project file at or above it
+    nearest: HashMap<PathBuf, Option<PathBuf>>,
+    configs: HashMap<PathBuf, ProjectConfig>,
+    /// Scan roots the search for project files stays within
+    roots: Vec<PathBuf>,
+    home: Option<PathBuf>,
+}
+
+impl Default for Projects {
+    fn default() -> Self {
+        Self::within(&[])
+    }
+}
+
+impl Projects {
+    /// Look for project files no higher than the given scan roots
+    ///
+    /// Like [`find_project_root`], the search never reaches the home
+    /// directory or `/`, roots or not.
+    pub fn within(roots: &[PathBuf]) -> Self {
+        Self {
+            nearest: HashMap::new(),
+            configs: HashMap::new(),
+            roots: roots.iter().map(|root| resolve_path(root)).collect(),
+            home: std::env::var_os("HOME").map(|home| resolve_path(Path::new(&home))),
+        }
+    }
+
+    fn may_search(&self, dir: &Path) -> bool {
+        dir.parent().is_some()
+            && self.home.as_deref() != Some(dir)
+            && (self.roots.is_empty() || self.roots.iter().any(|root| dir.starts_with(root)))
+    }
+
+    fn nearest_dir(&mut self, dir: &Path) -> Option<PathBuf> {
+        if let Some(found) = self.nearest.get(dir) {
+            return found.clone();
+        }
+        if !self.may_search(dir) {
+            return None;
+        }
+
+        let found = if dir.join(PROJECT_FILE).is_file() {
+            match ProjectConfig::load(dir) {
+                Ok(config) => {
+                    self.configs.insert(dir.to_path_buf(), config);
+                    Some(dir.to_path_buf())
+                }
+                Err(e) => {
+                    eprintln!("{} ignoring {}", "Warning:".yellow().bold(), e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let found = found.or_else(|| dir.parent().and_then(|parent| self.nearest_dir(parent)));
+
+        self.nearest.insert(dir.to_path_buf(), found.clone());
+        found
+    }
+
+    /// The project file governing `path`: its directory, its settings and
+    /// `path` relative to it
+    pub fn config_for(&mut self, path: &Path) -> Option<(PathBuf, &ProjectConfig, PathBuf)> {
+        let path = resolve_path(path);
+        let dir = self.nearest_dir(path.parent()?)?;
+        let relative = path.strip_prefix(&dir).ok()?.to_path_buf();
+        Some((dir.clone(), self.configs.get(&dir)?, relative))
+    }
+}
+
+/// Apply category overrides and mark kept items found under `roots`
+pub fn apply(items: &mut [DeletableItem], roots: &[PathBuf]) {
+    let mut projects = Projects::within(roots);
+
+    for item in items {
+        let Some((dir, config, relative)) = projects.config_for(&item.path) else {
+            continue;
+        };
+        if let Some(category) = config.category_of(&relative).filter(|&c| c != item.category) {
+            item.detected.get_or_insert(item.category);
+            item.category = category;
+        }
+        if config.keeps(&relative) {
+            item.kept = Some(dir.join(PROJECT_FILE));
+        }
+    }
+}
+
+/// Where a keep marker for `path` belongs: the nearest existing project file,
+/// else the root of the project `path` is in
+pub fn project_dir_for(path: &Path) -> PathBuf {
+    let mut projects = Projects::default();
+    match projects.config_for(path) {
+        Some((dir, _, _)) => dir,
+        None => find_project_root(path, &[]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_config() {
+        let config = ProjectConfig::parse(
+            r#"
+            keep = ["./build/", ".venv"]
+
+            [category]
+            "out" = "temp"
+
+            [retention]
+            node = { max_age = "60d" }
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.keeps(Path::new("build")));
+        assert!(!config.keeps(Path::new("dist")));
+
+        let mut edited = config.clone();
+        assert!(!edited.keep(Path::new("build")));
+        assert!(edited.keep(Path::new("dist")));
+        assert!(edited.unkeep(Path::new("build")));
+        assert_eq!(edited.keep, vec![".venv", "dist"]);
+        assert_eq!(config.category_of(Path::new("out")), Some(Category::TempFiles));
+        assert_eq!(config.rule_for(Category::NodeModules).unwrap().name, "node (.gigabroom.toml)");
+        assert!(config.rule_for(Category::RustTarget).is_none());
+
+        assert!(ProjectConfig::parse("[category]\nout = \"cobol\"").is_err());
+        assert!(ProjectConfig::parse("[retention]\nnode = { keep_recent = 2 }").is_err());
+    }
+}
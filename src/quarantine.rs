@@ -0,0 +1,250 @@
+//! # Quarantine Module
+//!
+//! A gigabroom-managed holding area for items removed by `clean --quarantine`.
+//!
+//! Items are renamed into `.gigabroom-quarantine/<timestamp>/` on their own
+//! filesystem (in the home directory when they share its filesystem, at the
+//! filesystem's mount point otherwise), which is instant. Each run directory
+//! has a `manifest.json` recording where everything came from, so items can be
+//! restored until `gigabroom purge` deletes runs past their undo window.
+
+use crate::remove::remove_path;
+use crate::types::{Category, DeletableItem};
+use crate::utils::{absolute_path, create_private_dir, data_dir, device_of, mount_top};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the quarantine directory on each filesystem
+pub const QUARANTINE_DIR: &str = ".gigabroom-quarantine";
+
+const MANIFEST: &str = "manifest.json";
+
+/// An item held in a quarantine run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedItem {
+    /// Entry name inside the run directory
+    pub name: String,
+    /// Where the item lived before it was quarantined
+    pub original: PathBuf,
+    pub size: u64,
+    pub category: Category,
+}
+
+/// Contents of a run's `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub created_at: DateTime<Local>,
+    pub items: Vec<QuarantinedItem>,
+}
+
+/// One `clean --quarantine` run on one filesystem
+#[derive(Debug, Clone)]
+pub struct QuarantineRun {
+    /// The run's timestamp directory name
+    pub id: String,
+    pub dir: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl QuarantineRun {
+    pub fn total_size(&self) -> u64 {
+        self.manifest.items.iter().map(|item| item.size).sum()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = self.dir.join(MANIFEST);
+        let json = serde_json::to_string_pretty(&self.manifest).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Returns the path to the list of quarantine directories gigabroom created
+fn roots_path() -> PathBuf {
+    data_dir().join("quarantine-roots.json")
+}
+
+fn load_roots() -> Vec<PathBuf> {
+    fs::read_to_string(roots_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn remember_root(root: &Path) -> Result<(), String> {
+    let mut roots = load_roots();
+    if roots.iter().any(|r| r == root) {
+        return Ok(());
+    }
+    roots.push(root.to_path_buf());
+
+    let path = roots_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(&roots).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Pick the quarantine directory on the same filesystem as `path`
+fn root_for(path: &Path) -> Result<PathBuf, String> {
+    let dev = device_of(path).ok_or_else(|| format!("{}: not found", path.display()))?;
+
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = match home {
+        Some(home) if device_of(&home) == Some(dev) => home,
+        _ => mount_top(path, dev),
+    };
+
+    let root = base.join(QUARANTINE_DIR);
+    create_private_dir(&root).map_err(|e| format!("{}: {}", root.display(), e))?;
+
+    if device_of(&root) != Some(dev) {
+        return Err(format!("{} is not on the same filesystem as {}", root.display(), path.display()));
+    }
+
+    Ok(root)
+}
+
+/// Quarantines the items of a single clean run
+///
+/// Run directories are created lazily, one per filesystem the items live on,
+/// and share the same timestamp.
+pub struct Quarantine {
+    created_at: DateTime<Local>,
+    runs: HashMap<PathBuf, QuarantineRun>,
+}
+
+impl Default for Quarantine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Quarantine {
+    pub fn new() -> Self {
+        Self {
+            created_at: Local::now(),
+            runs: HashMap::new(),
+        }
+    }
+
+    fn run_for(&mut self, root: PathBuf) -> Result<&mut QuarantineRun, String> {
+        if !self.runs.contains_key(&root) {
+            let stamp = self.created_at.format("%Y%m%d-%H%M%S").to_string();
+
+            // Runs started within the same second get a numeric suffix
+            let mut n = 1;
+            let (id, dir) = loop {
+                let id = if n == 1 { stamp.clone() } else { format!("{}-{}", stamp, n) };
+                let dir = root.join(&id);
+                match fs::create_dir(&dir) {
+                    Ok(()) => break (id, dir),
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+                    Err(e) => return Err(format!("{}: {}", dir.display(), e)),
+                }
+            };
+
+            remember_root(&root)?;
+            let run = QuarantineRun {
+                id,
+                dir,
+                manifest: Manifest {
+                    created_at: self.created_at,
+                    items: Vec::new(),
+                },
+            };
+            run.save()?;
+            self.runs.insert(root.clone(), run);
+        }
+
+        Ok(self.runs.get_mut(&root).expect("run was just inserted"))
+    }
+
+    /// Move an item into quarantine
+    ///
+    /// The manifest is rewritten after every item so an interrupted run can
+    /// still be restored.
+    pub fn add(&mut self, item: &DeletableItem) -> Result<(), String> {
+        let original = absolute_path(&item.path)?;
+        let run = self.run_for(root_for(&original)?)?;
+
+        let base_name = original
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = format!("{:04}-{}", run.manifest.items.len() + 1, base_name);
+
+        fs::rename(&original, run.dir.join(&name)).map_err(|e| e.to_string())?;
+
+        run.manifest.items.push(QuarantinedItem {
+            name,
+            original,
+            size: item.size,
+            category: item.category,
+        });
+        run.save()
+    }
+}
+
+/// Every quarantine run still on disk, oldest first
+pub fn runs() -> Vec<QuarantineRun> {
+    let mut runs: Vec<QuarantineRun> = load_roots()
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|entries| entries.filter_map(|e| e.ok()))
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let data = fs::read_to_string(dir.join(MANIFEST)).ok()?;
+            let manifest: Manifest = serde_json::from_str(&data).ok()?;
+            Some(QuarantineRun {
+                id: entry.file_name().to_string_lossy().into_owned(),
+                dir,
+                manifest,
+            })
+        })
+        .collect();
+
+    runs.sort_by_key(|run| run.manifest.created_at);
+    runs
+}
+
+/// Permanently delete a run and everything in it
+///
+/// Read-only trees (say, a quarantined Go module cache) are removed too.
+pub fn purge_run(run: &QuarantineRun) -> Result<(), String> {
+    remove_path(&run.dir, &|_| {}).map(|_| ()).map_err(|failure| failure.to_string())
+}
+
+/// Move an item out of quarantine, back to where it came from
+///
+/// The run directory is removed once its last item is restored.
+pub fn restore(run: &mut QuarantineRun, name: &str) -> Result<(), String> {
+    let idx = run
+        .manifest
+        .items
+        .iter()
+        .position(|item| item.name == name)
+        .ok_or_else(|| format!("{} is not in run {}", name, run.id))?;
+    let item = &run.manifest.items[idx];
+
+    if fs::symlink_metadata(&item.original).is_ok() {
+        return Err(format!("{} already exists", item.original.display()));
+    }
+
+    if let Some(parent) = item.original.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+
+    fs::rename(run.dir.join(name), &item.original).map_err(|e| e.to_string())?;
+    run.manifest.items.remove(idx);
+
+    if run.manifest.items.is_empty() {
+        purge_run(run)
+    } else {
+        run.save()
+    }
+}
@@ -0,0 +1,282 @@
+//! # Remove Module
+//!
+//! Recursive removal that reports bytes as they are freed and copes with
+//! read-only trees.
+//!
+//! `fs::remove_dir_all` gives no sign of life until the whole tree is gone and
+//! gives up on the first read-only directory (Go's module cache is all 0555
+//! directories). Walking the tree ourselves lets the cleaner drive a
+//! byte-level progress bar, restore write bits on directories we own, and say
+//! exactly how much was freed when something still can't be removed.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Why an item couldn't be (fully) removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// Owned by us, but still not removable (immutable flag, read-only mount, ...)
+    PermissionDenied,
+    /// In use, or a mount point
+    Busy,
+    /// Belongs to another user, so its permissions can't be fixed up
+    NotOwned,
+    /// Already gone when we got to it
+    Vanished,
+    Other,
+}
+
+impl FailureKind {
+    pub const fn label(&self) -> &'static str {
+        match self {
+            FailureKind::PermissionDenied => "Permission denied",
+            FailureKind::Busy => "Busy",
+            FailureKind::NotOwned => "Not owned",
+            FailureKind::Vanished => "Vanished",
+            FailureKind::Other => "Other errors",
+        }
+    }
+}
+
+/// An item that couldn't be removed, possibly after part of it was
+#[derive(Debug, Clone)]
+pub struct RemoveFailure {
+    pub kind: FailureKind,
+    pub message: String,
+    /// Bytes removed before giving up
+    pub freed: u64,
+    /// Bytes still on disk
+    pub left: u64,
+}
+
+impl RemoveFailure {
+    /// Failure of an operation that either moves the whole item or nothing
+    pub fn whole(path: &Path, size: u64, message: String) -> Self {
+        let vanished = fs::symlink_metadata(path).is_err();
+        Self {
+            kind: if vanished { FailureKind::Vanished } else { FailureKind::Other },
+            message,
+            freed: 0,
+            left: if vanished { 0 } else { size },
+        }
+    }
+
+    /// Returns true if some of the item was removed
+    pub fn is_partial(&self) -> bool {
+        self.freed > 0 && self.left > 0
+    }
+}
+
+impl fmt::Display for RemoveFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn is_owned(path: &Path) -> bool {
+    let euid = unsafe { libc::geteuid() };
+    fs::symlink_metadata(path).map(|m| m.uid() == euid).unwrap_or(false)
+}
+
+fn classify(e: &io::Error, path: &Path) -> FailureKind {
+    match e.raw_os_error() {
+        Some(libc::ENOENT) => FailureKind::Vanished,
+        Some(libc::EBUSY) | Some(libc::ETXTBSY) => FailureKind::Busy,
+        Some(libc::EACCES) | Some(libc::EPERM) if !is_owned(path) => FailureKind::NotOwned,
+        Some(libc::EACCES) | Some(libc::EPERM) => FailureKind::PermissionDenied,
+        _ => FailureKind::Other,
+    }
+}
+
+/// Give the owner full access to a directory, if it's ours and lacks it
+fn make_accessible(dir: &Path) -> bool {
+    let Ok(metadata) = fs::symlink_metadata(dir) else {
+        return false;
+    };
+    let mode = metadata.permissions().mode();
+    if !metadata.is_dir() || !is_owned(dir) || mode & 0o700 == 0o700 {
+        return false;
+    }
+    fs::set_permissions(dir, fs::Permissions::from_mode(mode | 0o700)).is_ok()
+}
+
+/// Run a filesystem operation, fixing up `dir`'s permissions and retrying once
+/// if it was refused
+fn with_access<T>(dir: &Path, op: impl Fn() -> io::Result<T>) -> io::Result<T> {
+    match op() {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied && make_accessible(dir) => op(),
+        result => result,
+    }
+}
+
+/// Removal of one item, remembering the first thing that went wrong
+struct Removal<'a> {
+    on_freed: &'a (dyn Fn(u64) + Sync),
+    /// The item's parent, which isn't ours to change permissions on
+    outside: &'a Path,
+    freed: u64,
+    error: Option<(FailureKind, String)>,
+}
+
+impl Removal<'_> {
+    fn access<T>(&self, dir: &Path, op: impl Fn() -> io::Result<T>) -> io::Result<T> {
+        if dir == self.outside {
+            op()
+        } else {
+            with_access(dir, op)
+        }
+    }
+
+    fn fail(&mut self, e: io::Error, path: &Path, owner: &Path) {
+        let kind = classify(&e, owner);
+        self.error.get_or_insert_with(|| (kind, format!("{}: {}", path.display(), e)));
+    }
+
+    /// Remove a file or symlink from `parent`; a file that's already gone is fine
+    fn remove_file(&mut self, path: &Path, parent: &Path, size: u64) -> bool {
+        match self.access(parent, || fs::remove_file(path)) {
+            Ok(()) => {
+                self.freed += size;
+                (self.on_freed)(size);
+                true
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => true,
+            Err(e) => {
+                self.fail(e, path, parent);
+                false
+            }
+        }
+    }
+
+    /// Remove a directory and everything in it; returns true if it's gone
+    fn remove_dir(&mut self, dir: &Path, parent: &Path) -> bool {
+        let entries = match self.access(dir, || fs::read_dir(dir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return true,
+            Err(e) => {
+                self.fail(e, dir, dir);
+                return false;
+            }
+        };
+
+        let mut emptied = true;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let removed = match entry.metadata() {
+                Ok(m) if m.is_dir() => self.remove_dir(&path, dir),
+                Ok(m) => self.remove_file(&path, dir, if m.is_file() { m.len() } else { 0 }),
+                Err(_) => self.remove_file(&path, dir, 0),
+            };
+            emptied &= removed;
+        }
+
+        // Removing a directory that still has children would only hide the real error
+        if !emptied {
+            return false;
+        }
+
+        match self.access(parent, || fs::remove_dir(dir)) {
+            Ok(()) => true,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => true,
+            Err(e) => {
+                self.fail(e, dir, parent);
+                false
+            }
+        }
+    }
+}
+
+/// Total size of the regular files under `path`
+fn size_on_disk(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Remove a file, symlink or directory tree, returning the bytes freed
+///
+/// `on_freed` is called with the size of every regular file removed.
+/// Directories inside the item that we own but can't write to or list get
+/// their owner bits restored and are retried; the item's parent is left
+/// alone. The walk keeps going past errors so as much as possible is freed;
+/// the failure reports the first error and what was left.
+pub fn remove_path(path: &Path, on_freed: &(dyn Fn(u64) + Sync)) -> Result<u64, RemoveFailure> {
+    let metadata = fs::symlink_metadata(path).map_err(|e| RemoveFailure {
+        kind: classify(&e, path),
+        message: format!("{}: {}", path.display(), e),
+        freed: 0,
+        left: 0,
+    })?;
+
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut removal = Removal {
+        on_freed,
+        outside: parent,
+        freed: 0,
+        error: None,
+    };
+
+    if metadata.is_dir() {
+        removal.remove_dir(path, parent);
+    } else {
+        removal.remove_file(path, parent, if metadata.is_file() { metadata.len() } else { 0 });
+    }
+
+    match removal.error {
+        None => Ok(removal.freed),
+        Some((kind, message)) => Err(RemoveFailure {
+            kind,
+            message,
+            freed: removal.freed,
+            left: size_on_disk(path),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_remove_read_only_tree() {
+        let root = std::env::temp_dir().join(format!("gigabroom-remove-{}", std::process::id()));
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("a/one"), [0u8; 10]).unwrap();
+        fs::write(root.join("a/b/two"), [0u8; 5]).unwrap();
+        std::os::unix::fs::symlink("/", root.join("a/link")).unwrap();
+
+        // Like Go's module cache: read-only files in read-only directories
+        for file in ["a/one", "a/b/two"] {
+            fs::set_permissions(root.join(file), fs::Permissions::from_mode(0o444)).unwrap();
+        }
+        for dir in ["a/b", "a", ""] {
+            fs::set_permissions(root.join(dir), fs::Permissions::from_mode(0o555)).unwrap();
+        }
+
+        let counted = AtomicU64::new(0);
+        let freed = remove_path(&root, &|bytes| {
+            counted.fetch_add(bytes, Ordering::Relaxed);
+        })
+        .unwrap();
+
+        assert!(!root.exists());
+        assert_eq!(freed, 15);
+        assert_eq!(counted.load(Ordering::Relaxed), 15);
+    }
+
+    #[test]
+    fn test_remove_vanished() {
+        let failure = remove_path(Path::new("/nonexistent/gigabroom"), &|_| {}).unwrap_err();
+        assert_eq!(failure.kind, FailureKind::Vanished);
+        assert!(!failure.is_partial());
+    }
+}
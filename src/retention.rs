@@ -0,0 +1,249 @@
+//! # Retention Module
+//!
+//! Per-category retention rules for `clean --policy`.
+//!
+//! A rule says when an item of its category may go: once its project has
+//! been inactive for `max_age`, if it's at least `min_size`, and never while
+//! its project is among the `keep_recent` most recently active ones in that
+//! category. A rule without conditions makes every item of its category
+//! eligible; categories without a rule are never cleaned by policy, except in
+//! projects whose `.gigabroom.toml` sets one.
+
+use crate::scanner::{find_project_root, resolve_item_ages};
+use crate::types::{AgeSource, Category, DeletableItem};
+use crate::utils::{format_size, parse_duration, parse_size};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// A retention rule as written in the config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionRule {
+    /// Eligible once the project has been inactive this long (e.g., "14d")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+    /// Only items at least this big are eligible (e.g., "100MB")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<String>,
+    /// The N most recently active projects keep theirs regardless
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_recent: Option<usize>,
+}
+
+/// A parsed rule for one category
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// Name the rule was configured under, shown as the reason
+    pub name: String,
+    pub category: Category,
+    pub max_age: Option<Duration>,
+    pub min_size: Option<u64>,
+    pub keep_recent: usize,
+}
+
+impl Rule {
+    pub fn parse(name: &str, category: Category, rule: &RetentionRule) -> Result<Self, String> {
+        Ok(Self {
+            name: name.to_string(),
+            category,
+            max_age: rule.max_age.as_deref().map(parse_duration).transpose()?,
+            min_size: rule.min_size.as_deref().map(parse_size).transpose()?,
+            keep_recent: rule.keep_recent.unwrap_or(0),
+        })
+    }
+}
+
+/// An item a rule allows cleaning, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eligible {
+    /// Index into the scanned items
+    pub index: usize,
+    pub reason: String,
+}
+
+fn days(duration: Duration) -> String {
+    format!("{}d", duration.as_secs() / 86400)
+}
+
+/// The project each item belongs to, and when that project was last worked on
+pub fn project_activity(items: &[DeletableItem], roots: &[PathBuf]) -> Vec<(PathBuf, SystemTime)> {
+    let times = resolve_item_ages(items, AgeSource::Project, roots);
+    items.iter().map(|item| find_project_root(&item.path, roots)).zip(times).collect()
+}
+
+/// Check every item against the rule for its category
+///
+/// `activity` holds each item's project and last activity, as returned by
+/// [`project_activity`]. `overrides` holds each item's own rule from its
+/// project's `.gigabroom.toml`, which replaces the age and size conditions of
+/// the category's rule, or applies on its own where the category has none.
+/// Eligible items come back in scan order.
+pub fn evaluate(
+    items: &[DeletableItem],
+    activity: &[(PathBuf, SystemTime)],
+    rules: &[Rule],
+    overrides: &[Option<Rule>],
+    now: SystemTime,
+) -> Vec<Eligible> {
+    let mut eligible = Vec::new();
+
+    let mut categories: Vec<Category> = rules.iter().map(|rule| rule.category).collect();
+    for own in overrides.iter().flatten() {
+        if !categories.contains(&own.category) {
+            categories.push(own.category);
+        }
+    }
+
+    for category in categories {
+        let rule = rules.iter().find(|rule| rule.category == category);
+        let members: Vec<usize> = (0..items.len())
+            .filter(|&i| items[i].category == category && (rule.is_some() || overrides[i].is_some()))
+            .collect();
+        let keep_recent = rule.map_or(0, |rule| rule.keep_recent);
+
+        // Projects whose items this rule keeps, most recently active first
+        let mut projects: Vec<(SystemTime, &PathBuf)> = members.iter().map(|&i| (activity[i].1, &activity[i].0)).collect();
+        projects.sort_by(|a, b| b.cmp(a));
+        projects.dedup_by(|a, b| a.1 == b.1);
+        let kept: HashSet<&PathBuf> = projects.iter().take(keep_recent).map(|(_, project)| *project).collect();
+
+        for i in members {
+            let (project, active) = &activity[i];
+            let Some(own) = overrides[i].as_ref().or(rule) else {
+                continue;
+            };
+            let (name, max_age, min_size) = (&own.name, own.max_age, own.min_size);
+            let inactive = now.duration_since(*active).unwrap_or(Duration::ZERO);
+            let size = items[i].size;
+
+            if kept.contains(project)
+                || max_age.is_some_and(|max| inactive < max)
+                || min_size.is_some_and(|min| size < min)
+            {
+                continue;
+            }
+
+            let mut reasons = Vec::new();
+            if let Some(max) = max_age {
+                reasons.push(format!("inactive {} (over {})", days(inactive), days(max)));
+            }
+            if let Some(min) = min_size {
+                reasons.push(format!("{} (over {})", format_size(size), format_size(min)));
+            }
+            if keep_recent > 0 {
+                reasons.push(format!("not among the {} most recent", keep_recent));
+            }
+            if reasons.is_empty() {
+                reasons.push("always".to_string());
+            }
+
+            eligible.push(Eligible {
+                index: i,
+                reason: format!("{}: {}", name, reasons.join(", ")),
+            });
+        }
+    }
+
+    eligible.sort_by_key(|e| e.index);
+    eligible
+}
+
+/// Show which items the policy allows cleaning, with the rule behind each
+pub fn show_eligible(items: &[DeletableItem], eligible: &[Eligible]) {
+    println!("\n{}", "📋 Retention policy".bright_cyan().bold());
+
+    for (n, e) in eligible.iter().enumerate() {
+        let item = &items[e.index];
+        println!(
+            "  {:3}. {:16} {:24} {:>10}  {}",
+            n + 1,
+            item.category.name(),
+            item.project_name.chars().take(24).collect::<String>().bright_cyan(),
+            format_size(item.size),
+            e.reason.dimmed()
+        );
+    }
+
+    let total: u64 = eligible.iter().map(|e| items[e.index].size).sum();
+    println!(
+        "{} {} of {} items, {}",
+        "Eligible:".bright_white().bold(),
+        eligible.len(),
+        items.len(),
+        format_size(total).bright_green().bold()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_rules() {
+        let now = SystemTime::now();
+        let ago = |days: u64| now - Duration::from_secs(days * 86400);
+        let item = |category, size| {
+            DeletableItem::new(PathBuf::from("/p/x"), size, category, "p".to_string(), now)
+        };
+
+        let items = vec![
+            item(Category::NodeModules, 100),
+            item(Category::NodeModules, 100),
+            item(Category::RustTarget, 100),
+            item(Category::RustTarget, 5000),
+            item(Category::OSJunk, 1),
+            item(Category::TempFiles, 1),
+        ];
+        let activity = vec![
+            (PathBuf::from("/a"), ago(20)),
+            (PathBuf::from("/b"), ago(5)),
+            (PathBuf::from("/c"), ago(60)),
+            (PathBuf::from("/d"), ago(90)),
+            (PathBuf::from("/e"), ago(0)),
+            (PathBuf::from("/f"), ago(400)),
+        ];
+        let rule = |name: &str, category, max_age: Option<&str>, min_size: Option<&str>, keep_recent| {
+            let config = RetentionRule {
+                max_age: max_age.map(str::to_string),
+                min_size: min_size.map(str::to_string),
+                keep_recent,
+            };
+            Rule::parse(name, category, &config).unwrap()
+        };
+        let rules = vec![
+            rule("node", Category::NodeModules, Some("14d"), None, None),
+            rule("rust", Category::RustTarget, Some("30d"), Some("1KB"), Some(0)),
+            rule("os-junk", Category::OSJunk, None, None, None),
+        ];
+
+        let none = vec![None; items.len()];
+        let eligible = evaluate(&items, &activity, &rules, &none, now);
+        let indices: Vec<usize> = eligible.iter().map(|e| e.index).collect();
+        assert_eq!(indices, vec![0, 3, 4]);
+        assert_eq!(eligible[0].reason, "node: inactive 20d (over 14d)");
+        assert_eq!(eligible[2].reason, "os-junk: always");
+
+        // A project's own rule replaces the category's
+        let mut overrides = none.clone();
+        overrides[0] = Some(rule("node (project)", Category::NodeModules, Some("30d"), None, None));
+        overrides[1] = Some(rule("node (project)", Category::NodeModules, Some("1d"), None, None));
+        let eligible = evaluate(&items, &activity, &rules, &overrides, now);
+        assert_eq!(eligible.iter().map(|e| e.index).collect::<Vec<_>>(), vec![1, 3, 4]);
+        assert_eq!(eligible[0].reason, "node (project): inactive 5d (over 1d)");
+
+        // A project's rule applies even where the config has none
+        let mut overrides = none.clone();
+        overrides[5] = Some(rule("temp (project)", Category::TempFiles, Some("1y"), None, None));
+        let eligible = evaluate(&items, &activity, &rules, &overrides, now);
+        assert_eq!(eligible.iter().map(|e| e.index).collect::<Vec<_>>(), vec![0, 3, 4, 5]);
+        assert_eq!(eligible[3].reason, "temp (project): inactive 400d (over 365d)");
+
+        // The most recently active project keeps its target
+        let rules = vec![rule("rust", Category::RustTarget, None, None, Some(1))];
+        let eligible = evaluate(&items, &activity, &rules, &none, now);
+        assert_eq!(eligible.iter().map(|e| e.index).collect::<Vec<_>>(), vec![3]);
+    }
+}
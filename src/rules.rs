@@ -0,0 +1,240 @@
+//! # Rules Module
+//!
+//! User-defined detection rules loaded from `~/.config/gigabroom/rules.toml`.
+//!
+//! Each rule declares file name globs, optional marker files that must sit
+//! next to the match, a category label and a danger level. Rules are
+//! registered as detectors alongside the built-in ones, so they show up in
+//! scans, the interactive menus and JSON output like any other category.
+//!
+//! ```toml
+//! [[rule]]
+//! name = "Buck output"
+//! patterns = [".buck-out", "buck-out"]
+//! markers = [".buckconfig"]
+//!
+//! [[rule]]
+//! name = "Generated sources"
+//! patterns = ["_generated", "gen-src"]
+//! danger = "dangerous"
+//! ```
+
+use crate::detectors::Detector;
+use crate::types::{Category, CustomCategory};
+use crate::utils::config_dir;
+use colored::*;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Priority given to user rules that don't set one (above every built-in)
+const DEFAULT_RULE_PRIORITY: i32 = 50;
+
+/// Returns the path to the user rules file
+pub fn rules_path() -> PathBuf {
+    config_dir().join("rules.toml")
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleConfig>,
+}
+
+/// Kind of filesystem entry a rule applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    #[default]
+    Dir,
+    File,
+    Any,
+}
+
+/// How risky it is to delete what a rule matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DangerLevel {
+    #[default]
+    Safe,
+    Dangerous,
+}
+
+/// A single `[[rule]]` entry as written in the rules file
+#[derive(Debug, Deserialize)]
+struct RuleConfig {
+    name: String,
+    patterns: Vec<String>,
+    #[serde(default)]
+    markers: Vec<String>,
+    #[serde(default)]
+    kind: EntryKind,
+    #[serde(default)]
+    danger: DangerLevel,
+    priority: Option<i32>,
+}
+
+/// A compiled user-defined rule
+#[derive(Debug, Clone)]
+pub struct CustomRule {
+    category: CustomCategory,
+    globs: GlobSet,
+    literal_names: Vec<String>,
+    markers: Vec<String>,
+    kind: EntryKind,
+    priority: i32,
+}
+
+impl CustomRule {
+    fn compile(config: RuleConfig) -> Result<Self, String> {
+        if config.patterns.is_empty() {
+            return Err(format!("rule '{}' has no patterns", config.name));
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &config.patterns {
+            let glob = Glob::new(pattern)
+                .map_err(|e| format!("rule '{}': invalid pattern '{}': {}", config.name, pattern, e))?;
+            builder.add(glob);
+        }
+        let globs = builder
+            .build()
+            .map_err(|e| format!("rule '{}': {}", config.name, e))?;
+
+        let literal_names = config
+            .patterns
+            .iter()
+            .filter(|p| !p.contains(['*', '?', '[', '{']))
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            category: CustomCategory::new(&config.name, config.danger == DangerLevel::Dangerous),
+            globs,
+            literal_names,
+            markers: config.markers,
+            kind: config.kind,
+            priority: config.priority.unwrap_or(DEFAULT_RULE_PRIORITY),
+        })
+    }
+}
+
+impl Detector for CustomRule {
+    fn category(&self) -> Category {
+        Category::Custom(self.category)
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn matches_name(&self, file_name: &str, _path: &Path) -> bool {
+        self.globs.is_match(file_name)
+    }
+
+    fn confirm(&self, path: &Path, is_dir: bool) -> bool {
+        let kind_ok = match self.kind {
+            EntryKind::Dir => is_dir,
+            EntryKind::File => !is_dir,
+            EntryKind::Any => true,
+        };
+
+        kind_ok
+            && path
+                .parent()
+                .map(|p| self.markers.iter().all(|marker| p.join(marker).exists()))
+                .unwrap_or(false)
+    }
+
+    fn indexed_names(&self) -> Vec<String> {
+        self.literal_names.clone()
+    }
+}
+
+/// Parse and compile rules from the contents of a rules file
+pub fn parse_rules(contents: &str) -> Result<Vec<CustomRule>, String> {
+    let file: RulesFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    file.rule.into_iter().map(CustomRule::compile).collect()
+}
+
+/// Rules loaded from the user's rules file, read once per process
+///
+/// A missing file means no custom rules. A malformed file is reported on
+/// stderr and ignored so scans still work with the built-in detectors.
+pub fn loaded_rules() -> &'static [CustomRule] {
+    static RULES: OnceLock<Vec<CustomRule>> = OnceLock::new();
+
+    RULES.get_or_init(|| {
+        let path = rules_path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+
+        parse_rules(&contents).unwrap_or_else(|e| {
+            eprintln!(
+                "{} ignoring {}: {}",
+                "Warning:".yellow().bold(),
+                path.display(),
+                e
+            );
+            Vec::new()
+        })
+    })
+}
+
+/// Categories declared by the user's rules
+pub fn custom_categories() -> Vec<Category> {
+    let mut categories: Vec<Category> = Vec::new();
+    for rule in loaded_rules() {
+        let category = rule.category();
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    categories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            r#"
+            [[rule]]
+            name = "Buck output"
+            patterns = [".buck-out", "gen-*"]
+            markers = [".buckconfig"]
+
+            [[rule]]
+            name = "Scratch"
+            patterns = ["*.scratch"]
+            kind = "file"
+            danger = "dangerous"
+            priority = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].category().name(), "Buck output");
+        assert!(!rules[0].category().is_dangerous());
+        assert!(rules[0].matches_name("gen-src", Path::new("gen-src")));
+        assert!(!rules[0].matches_name("src", Path::new("src")));
+        assert_eq!(rules[0].indexed_names(), vec![".buck-out".to_string()]);
+        assert_eq!(rules[0].priority(), DEFAULT_RULE_PRIORITY);
+
+        assert!(rules[1].category().is_dangerous());
+        assert_eq!(rules[1].priority(), 5);
+    }
+
+    #[test]
+    fn test_parse_rules_rejects_bad_input() {
+        assert!(parse_rules("[[rule]]\nname = \"x\"\npatterns = []").is_err());
+        assert!(parse_rules("[[rule]]\nname = \"x\"\npatterns = [\"a[\"]").is_err());
+        assert!(parse_rules("[[rule]]\nname = \"x\"\npatterns = [\"a\"]\ndanger = \"scary\"").is_err());
+    }
+}
@@ -1,262 +1,179 @@
-use crate::types::{Category, DeletableItem};
-use crate::utils::{get_project_name, is_cargo_target};
+use crate::cache::unchanged_since;
+use crate::cargo::{attribute_targets, merge_targets, resolve_targets};
+use crate::detectors::registry;
+use crate::exclude::{Excludes, IGNORE_FILE};
+use crate::quarantine::QUARANTINE_DIR;
+use crate::types::{AgeSource, CachedDir, CachedSize, Category, DeletableItem, ScanIndex};
+use crate::utils::get_project_name;
+#[cfg(target_os = "linux")]
+use crate::println_unless_quiet;
+#[cfg(target_os = "linux")]
+use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::process::Stdio;
 use std::sync::{Arc, Mutex};
+#[cfg(target_os = "linux")]
+use std::time::Duration;
 use std::time::SystemTime;
 use walkdir::WalkDir;
 
 /// Determine if a path is deletable and return its category
+///
+/// Delegates to the process-wide [`DetectorRegistry`](crate::detectors::DetectorRegistry).
 #[inline]
 pub fn is_deletable(path: &Path) -> Option<Category> {
-    let file_name = path.file_name()?.to_str()?;
-
-    // Check if it's a directory first
-    let is_dir = path.is_dir();
-
-    match file_name {
-        // Rust
-        "target" if is_dir && is_cargo_target(path) => Some(Category::RustTarget),
-
-        // JavaScript/TypeScript
-        "node_modules" if is_dir => Some(Category::NodeModules),
-
-        // Python
-        "__pycache__" if is_dir => Some(Category::PythonCache),
-        ".pytest_cache" if is_dir => Some(Category::PythonCache),
-        ".tox" if is_dir => Some(Category::PythonCache),
-        "venv" if is_dir => Some(Category::PythonCache),
-        ".venv" if is_dir => Some(Category::PythonCache),
-
-        // Java - Maven
-        "target" if is_dir && is_maven_target(path) => Some(Category::MavenTarget),
-
-        // Java - Gradle
-        "build" if is_dir && is_gradle_build(path) => Some(Category::GradleBuild),
-        ".gradle" if is_dir => Some(Category::GradleBuild),
-
-        // PHP - Composer (check BEFORE Go!)
-        "vendor" if is_dir && is_composer_vendor(path) => Some(Category::PHPVendor),
-
-        // Go (check AFTER PHP!)
-        "vendor" if is_dir && is_go_vendor(path) => Some(Category::GoVendor),
-
-        // C/C++
-        "CMakeFiles" if is_dir => Some(Category::CCache),
-
-        // .NET
-        "bin" if is_dir && is_dotnet_build(path) => Some(Category::DotNetBuild),
-        "obj" if is_dir && is_dotnet_build(path) => Some(Category::DotNetBuild),
-        "packages" if is_dir && is_dotnet_packages(path) => Some(Category::DotNetBuild),
-
-        // Swift
-        ".build" if is_dir && is_swift_build(path) => Some(Category::SwiftBuild),
-        "DerivedData" if is_dir => Some(Category::SwiftBuild),
-
-        // IDE Caches
-        ".idea" if is_dir => Some(Category::IDECache),
-        ".vscode" if is_dir => Some(Category::IDECache),
-        ".vs" if is_dir => Some(Category::IDECache),
-
-        // Ruby - Bundler
-        "vendor" if is_dir && is_ruby_bundler(path) => Some(Category::RubyGems),
-        ".bundle" if is_dir => Some(Category::RubyGems),
-
-        // OS Junk Files
-        ".DS_Store" => Some(Category::OSJunk),
-        "Thumbs.db" => Some(Category::OSJunk),
-        "desktop.ini" => Some(Category::OSJunk),
-        ".localized" => Some(Category::OSJunk),
-
-        // Temp/Cache directories
-        ".sass-cache" if is_dir => Some(Category::TempFiles),
-        ".parcel-cache" if is_dir => Some(Category::TempFiles),
-        ".cache" if is_dir => Some(Category::TempFiles),
-
-        // General build
-        "build" if is_dir => Some(Category::BuildCache),
-        "dist" if is_dir => Some(Category::BuildCache),
-        "out" if is_dir => Some(Category::BuildCache),
-
-        _ => {
-            // Python bytecode files
-            if file_name.ends_with(".pyc") || file_name.ends_with(".pyo") {
-                return Some(Category::PythonCache);
-            }
-
-            // C/C++ object files
-            if file_name.ends_with(".o") || file_name.ends_with(".a") || file_name == "a.out" {
-                return Some(Category::CCache);
-            }
-
-            // Log files
-            if file_name.ends_with(".log") {
-                return Some(Category::TempFiles);
-            }
-
-            // Temp files
-            if file_name.ends_with(".tmp") || file_name.ends_with(".temp") {
-                return Some(Category::TempFiles);
-            }
-
-            // Package manager global caches (DANGEROUS!)
-            let path_str = path.to_str()?;
-            if path_str.contains("/.npm/_cacache") {
-                return Some(Category::PackageCache);
-            }
-            if path_str.contains("/.cache/pip") {
-                return Some(Category::PackageCache);
-            }
-            if path_str.contains("/.cache/yarn") {
-                return Some(Category::PackageCache);
-            }
-            if path_str.contains("/.m2/repository") {
-                return Some(Category::PackageCache);
-            }
-
-            None
-        }
-    }
+    registry().detect(path)
 }
 
-/// Check if a path is a Maven target directory
-#[inline]
-fn is_maven_target(path: &Path) -> bool {
-    path.parent()
-        .map(|p| p.join("pom.xml").exists())
-        .unwrap_or(false)
-}
-
-/// Check if a path is a Gradle build directory
-#[inline]
-fn is_gradle_build(path: &Path) -> bool {
-    path.parent()
-        .map(|p| p.join("build.gradle").exists() || p.join("build.gradle.kts").exists())
-        .unwrap_or(false)
-}
-
-/// Check if a path is a PHP Composer vendor directory
-#[inline]
-fn is_composer_vendor(path: &Path) -> bool {
-    path.parent()
-        .map(|p| p.join("composer.json").exists())
-        .unwrap_or(false)
-}
-
-/// Check if a path is a Go vendor directory
-#[inline]
-fn is_go_vendor(path: &Path) -> bool {
-    path.parent()
-        .map(|p| p.join("go.mod").exists() || p.join("go.sum").exists())
-        .unwrap_or(false)
-}
-
-/// Check if a path is a .NET build directory (bin/obj)
+/// Calculate directory size in parallel using all available cores
 #[inline]
-fn is_dotnet_build(path: &Path) -> bool {
-    let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-    path.parent()
-        .map(|p| {
-            // First check: parent must have a .NET project file
-            let has_project_file = p.read_dir()
-                .ok()
-                .map(|entries| {
-                    entries
-                        .filter_map(|e| e.ok())
-                        .any(|e| {
-                            e.file_name()
-                                .to_str()
-                                .map(|s| {
-                                    s.ends_with(".csproj")
-                                        || s.ends_with(".vbproj")
-                                        || s.ends_with(".fsproj")
-                                })
-                                .unwrap_or(false)
-                        })
-                })
-                .unwrap_or(false);
-
-            if !has_project_file {
-                return false;
-            }
-
-            // Second check: BOTH bin and obj should exist (typical .NET structure)
-            // This prevents false positives with system directories
-            if dir_name == "bin" {
-                p.join("obj").exists()
-            } else if dir_name == "obj" {
-                p.join("bin").exists()
+pub fn calculate_dir_size_parallel(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .par_bridge() // Parallel bridge for iterator
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            if entry.file_type().is_file() {
+                entry.metadata().ok().map(|m| m.len())
             } else {
-                false
+                None
             }
         })
-        .unwrap_or(false)
-}
-
-/// Check if a path is a .NET packages directory
-#[inline]
-fn is_dotnet_packages(path: &Path) -> bool {
-    // packages/ directory is typically in solution root
-    path.parent()
-        .and_then(|p| p.parent())
-        .map(|p| {
-            p.read_dir()
-                .ok()
-                .map(|entries| {
-                    entries
-                        .filter_map(|e| e.ok())
-                        .any(|e| {
-                            e.file_name()
-                                .to_str()
-                                .map(|s| s.ends_with(".sln"))
-                                .unwrap_or(false)
-                        })
-                })
-                .unwrap_or(false)
-        })
-        .unwrap_or(false)
-}
-
-/// Check if a path is a Swift build directory
-#[inline]
-fn is_swift_build(path: &Path) -> bool {
-    path.parent()
-        .map(|p| p.join("Package.swift").exists())
-        .unwrap_or(false)
+        .sum()
 }
 
-/// Check if a path is a Ruby Bundler vendor directory
-#[inline]
-fn is_ruby_bundler(path: &Path) -> bool {
-    // Check if parent has Gemfile
-    path.parent()
-        .map(|p| p.join("Gemfile").exists() || p.join("Gemfile.lock").exists())
-        .unwrap_or(false)
+/// Newest mtime of any directory inside a tree
+///
+/// Adding, removing or renaming anything inside a directory updates its
+/// mtime, so this changes whenever the tree's contents do. Only directories
+/// are stat'ed, which is much cheaper than sizing every file.
+fn tree_signature(path: &Path) -> SystemTime {
+    WalkDir::new(path)
+        .into_iter()
+        .par_bridge()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
 }
 
-/// Calculate directory size in parallel using all available cores
-#[inline]
-pub fn calculate_dir_size_parallel(path: &Path) -> u64 {
+/// Size and signature of a tree in a single pass
+fn size_and_signature(path: &Path) -> (u64, SystemTime) {
     WalkDir::new(path)
         .into_iter()
-        .par_bridge() // Parallel bridge for iterator
+        .par_bridge()
         .filter_map(|e| e.ok())
         .filter_map(|entry| {
-            if entry.file_type().is_file() {
-                entry.metadata().ok().map(|m| m.len())
+            let metadata = entry.metadata().ok()?;
+            if entry.file_type().is_dir() {
+                Some((0, metadata.modified().ok()?))
+            } else if entry.file_type().is_file() {
+                Some((metadata.len(), SystemTime::UNIX_EPOCH))
             } else {
                 None
             }
         })
-        .sum()
+        .reduce(
+            || (0, SystemTime::UNIX_EPOCH),
+            |(size_a, sig_a), (size_b, sig_b)| (size_a + size_b, sig_a.max(sig_b)),
+        )
+}
+
+/// Directory walk that reuses and refreshes the scan index
+struct IndexedWalk<'a> {
+    index: &'a mut ScanIndex,
+    reuse: bool,
+    now: SystemTime,
+    reused: u64,
+}
+
+impl IndexedWalk<'_> {
+    /// List a directory, from the index if it hasn't changed since
+    ///
+    /// Subdirectories whose names aren't valid UTF-8 can't be stored in the
+    /// index; they are returned separately and the listing isn't cached.
+    fn list(&mut self, dir: &Path, key: &Path) -> Option<(CachedDir, Vec<PathBuf>)> {
+        let mtime = fs::metadata(dir).ok()?.modified().ok()?;
+
+        if self.reuse {
+            if let Some(cached) = self.index.dirs.get_mut(key) {
+                if unchanged_since(mtime, cached.mtime, cached.verified) {
+                    cached.verified = self.now;
+                    self.reused += 1;
+                    return Some((cached.clone(), Vec::new()));
+                }
+            }
+        }
+
+        let mut listing = CachedDir {
+            mtime,
+            verified: self.now,
+            subdirs: Vec::new(),
+            artifact_files: Vec::new(),
+            cargo_manifest: false,
+            ignore_file: false,
+        };
+        let mut unnamed = Vec::new();
+
+        for entry in fs::read_dir(dir).ok()?.filter_map(|e| e.ok()) {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                if is_dir {
+                    unnamed.push(entry.path());
+                }
+                continue;
+            };
+
+            if name == "Cargo.toml" {
+                listing.cargo_manifest = true;
+            }
+            if name == IGNORE_FILE {
+                listing.ignore_file = true;
+            }
+
+            // Quarantined items are already dealt with
+            if name == QUARANTINE_DIR {
+                continue;
+            }
+
+            if is_dir {
+                listing.subdirs.push(name);
+            } else if let Some(category) = is_deletable(&entry.path()) {
+                listing.artifact_files.push((name, category));
+            }
+        }
+
+        if unnamed.is_empty() {
+            self.index.dirs.insert(key.to_path_buf(), listing.clone());
+        }
+
+        Some((listing, unnamed))
+    }
 }
 
 /// Scan directory for deletable items with parallel processing
-pub fn scan_directory(path: &Path, max_depth: usize, quiet: bool) -> Vec<DeletableItem> {
+///
+/// Directories that haven't changed since they were recorded in `index` are
+/// not re-read, and artifacts whose contents haven't changed are not re-sized.
+/// With `reuse` off everything is read fresh; the index is updated either way.
+/// Excluded paths are skipped before they're listed, so excluded subtrees are
+/// never walked.
+pub fn scan_directory(
+    path: &Path,
+    max_depth: usize,
+    index: &mut ScanIndex,
+    excludes: &mut Excludes,
+    reuse: bool,
+    quiet: bool,
+) -> Vec<DeletableItem> {
     let pb = if quiet {
         ProgressBar::hidden()
     } else {
@@ -265,77 +182,106 @@ pub fn scan_directory(path: &Path, max_depth: usize, quiet: bool) -> Vec<Deletab
 
     pb.set_style(
         ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} Scanning... [{elapsed_precise}] {pos} items scanned | {msg}")
+            .template("{spinner:.cyan} Scanning... [{elapsed_precise}] {pos} directories scanned | {msg}")
             .unwrap()
             .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
     );
 
-    let scanned_count = Arc::new(Mutex::new(0u64));
-    let pending_deletables = Arc::new(Mutex::new(Vec::new()));
-    let found_count = Arc::new(Mutex::new(0u64));
+    // The index is keyed by absolute paths so scans of different roots share it
+    let abs_root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let index_key = |p: &Path| -> PathBuf {
+        p.strip_prefix(path)
+            .map(|rel| abs_root.join(rel))
+            .unwrap_or_else(|_| p.to_path_buf())
+    };
+
+    let now = SystemTime::now();
+    let mut walk = IndexedWalk {
+        index,
+        reuse,
+        now,
+        reused: 0,
+    };
+
+    let mut pending: Vec<(PathBuf, Category)> = Vec::new();
+    let mut cargo_projects: Vec<PathBuf> = Vec::new();
+    let mut scanned_count = 0u64;
+
+    // The root is reported if deletable, but still scanned
+    if let Some(category) = is_deletable(path) {
+        pending.push((path.to_path_buf(), category));
+    }
 
     // First pass: collect deletable entries with smart filtering
-    // Use filter_entry to prevent descending into deletable directories
-    for entry in WalkDir::new(path)
-        .max_depth(max_depth)
-        .into_iter()
-        .filter_entry(|e| {
-            // Don't descend into directories that are themselves deletable
-            // (except the root path we're scanning)
-            if e.path() == path {
-                return true;
-            }
+    // Deletable directories are recorded, then never descended into
+    let mut stack = vec![(path.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        if depth >= max_depth {
+            continue;
+        }
 
-            // Check if this entry is deletable
-            if let Some(_category) = is_deletable(e.path()) {
-                // It's deletable, so we don't want to descend into it
-                return false;
-            }
+        let key = index_key(&dir);
+        let Some((listing, unnamed)) = walk.list(&dir, &key) else {
+            continue;
+        };
+        excludes.visit(&key, listing.ignore_file);
 
-            true
-        })
-        .filter_map(|e| e.ok())
-    {
-        let entry_path = entry.path();
+        scanned_count += 1;
+        if scanned_count.is_multiple_of(100) {
+            pb.set_position(scanned_count);
+            pb.tick();
+        }
 
-        {
-            let mut count = scanned_count.lock().unwrap();
-            *count += 1;
-            if *count % 100 == 0 {
-                pb.set_position(*count);
-                pb.tick();
-            }
+        // Remember Cargo projects so their real target dirs can be resolved
+        if listing.cargo_manifest {
+            cargo_projects.push(dir.clone());
         }
 
-        // Check if this entry itself is deletable
-        if let Some(category) = is_deletable(entry_path) {
-            let mut found = found_count.lock().unwrap();
-            *found += 1;
+        let found_before = pending.len();
+        for (name, category) in &listing.artifact_files {
+            if !excludes.is_excluded(&key.join(name), false) {
+                pending.push((dir.join(name), *category));
+            }
+        }
 
-            pending_deletables
-                .lock()
-                .unwrap()
-                .push((entry_path.to_path_buf(), category));
+        // Subdirectories are always re-checked: whether one is deletable can
+        // depend on what's inside it (CACHEDIR.TAG, for instance)
+        let subdirs = listing.subdirs.iter().map(|name| dir.join(name)).chain(unnamed);
+        for subdir in subdirs {
+            if excludes.is_excluded(&index_key(&subdir), true) {
+                continue;
+            }
+            match is_deletable(&subdir) {
+                Some(category) => pending.push((subdir, category)),
+                None => stack.push((subdir, depth + 1)),
+            }
+        }
 
-            // Update message with latest find
-            if !quiet {
-                let file_name = entry_path.file_name()
+        // Update message with latest find
+        if !quiet && pending.len() > found_before {
+            if let Some((latest, category)) = pending.last() {
+                let file_name = latest.file_name()
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown");
-                pb.set_message(format!("Found {} items | Latest: {} ({})", *found, file_name, category.name()));
+                pb.set_message(format!("Found {} items | Latest: {} ({})", pending.len(), file_name, category.name()));
             }
         }
     }
 
-    let final_count = *scanned_count.lock().unwrap();
-    let total_found = *found_count.lock().unwrap();
-    pb.set_position(final_count);
+    pb.set_position(scanned_count);
     pb.finish_with_message(format!(
-        "✓ Scanned {} items, found {} deletable directories. Calculating sizes...",
-        final_count, total_found
+        "✓ Scanned {} directories ({} unchanged), found {} deletable directories. Calculating sizes...",
+        scanned_count, walk.reused, pending.len()
     ));
 
-    let pending = pending_deletables.lock().unwrap().clone();
+    // Resolve shared, relocated and workspace-level Cargo targets
+    let cargo_targets = resolve_targets(&cargo_projects, path);
+    merge_targets(&mut pending, &cargo_targets);
+
+    // Targets outside the tree were never seen by the walk, so check them here
+    pending.retain(|(item_path, category)| {
+        *category != Category::RustTarget || !excludes.is_excluded_tree(&index_key(item_path))
+    });
 
     // Second pass: calculate sizes in parallel using all cores
     let size_pb = Arc::new(Mutex::new(if quiet {
@@ -354,15 +300,28 @@ pub fn scan_directory(path: &Path, max_depth: usize, quiet: bool) -> Vec<Deletab
         );
     }
 
+    let cached_sizes = &walk.index.sizes;
+
     // Parallel processing with rayon - uses all available cores
-    let items: Vec<DeletableItem> = pending
+    let sized: Vec<(DeletableItem, Option<(PathBuf, CachedSize)>)> = pending
         .par_iter()
         .map(|(item_path, category)| {
             let metadata = fs::metadata(item_path).ok();
-            let size = if item_path.is_dir() {
-                calculate_dir_size_parallel(item_path)
+
+            let (size, cache_entry) = if item_path.is_dir() {
+                let key = index_key(item_path);
+                let cached = cached_sizes.get(&key).filter(|_| reuse);
+
+                // Re-size only when something inside changed
+                let reusable = cached.filter(|c| unchanged_since(tree_signature(item_path), c.signature, c.verified));
+                let (size, signature) = match reusable {
+                    Some(c) => (c.size, c.signature),
+                    None => size_and_signature(item_path),
+                };
+
+                (size, Some((key, CachedSize { signature, verified: now, size })))
             } else {
-                metadata.as_ref().map(|m| m.len()).unwrap_or(0)
+                (metadata.as_ref().map(|m| m.len()).unwrap_or(0), None)
             };
 
             let last_modified = metadata
@@ -379,163 +338,200 @@ pub fn scan_directory(path: &Path, max_depth: usize, quiet: bool) -> Vec<Deletab
             }
             size_pb.lock().unwrap().inc(1);
 
-            DeletableItem::new(item_path.clone(), size, *category, project_name, last_modified)
+            (
+                DeletableItem::new(item_path.clone(), size, *category, project_name, last_modified),
+                cache_entry,
+            )
         })
         .collect();
 
     size_pb.lock().unwrap().finish_and_clear();
 
+    let mut items = Vec::with_capacity(sized.len());
+    for (item, cache_entry) in sized {
+        if let Some((key, entry)) = cache_entry {
+            walk.index.sizes.insert(key, entry);
+        }
+        items.push(item);
+    }
+
+    attribute_targets(&mut items, &cargo_targets);
+
     items
 }
 
 // ============================================================================
-// System Indexing Functions (macOS Spotlight)
+// Age Resolution
 // ============================================================================
 
-#[cfg(target_os = "macos")]
-fn find_with_mdfind(base_path: &Path, query: &str) -> Result<Vec<PathBuf>, String> {
-    let output = Command::new("mdfind")
-        .arg("-onlyin")
-        .arg(base_path)
-        .arg(query)
-        .output()
-        .map_err(|e| format!("Failed to execute mdfind: {}", e))?;
+/// Files and directories that mark the root of a project
+const PROJECT_MARKERS: &[&str] = &[
+    ".git",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "requirements.txt",
+    "composer.json",
+    "Gemfile",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "go.mod",
+    "CMakeLists.txt",
+    "Package.swift",
+];
+
+/// Version control metadata that doesn't count as project activity
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Most entries looked at when measuring a project's activity
+const ACTIVITY_WALK_LIMIT: usize = 100_000;
+
+/// Find the root of the project that owns an artifact
+///
+/// Walks up from the artifact looking for a project marker, falling back to
+/// the artifact's parent directory when none is found. The climb never leaves
+/// the scan root the artifact was found under, and never reaches the home
+/// directory or `/`.
+pub fn find_project_root(item_path: &Path, roots: &[PathBuf]) -> PathBuf {
+    let parent = item_path.parent().unwrap_or(item_path);
+    let root = roots
+        .iter()
+        .filter(|root| parent.starts_with(root))
+        .max_by_key(|root| root.as_os_str().len());
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+
+    parent
+        .ancestors()
+        .take_while(|dir| {
+            dir.parent().is_some()
+                && home.as_deref() != Some(*dir)
+                && root.is_none_or(|root| dir.starts_with(root))
+        })
+        .find(|dir| PROJECT_MARKERS.iter().any(|marker| dir.join(marker).exists()))
+        .unwrap_or(parent)
+        .to_path_buf()
+}
 
-    if !output.status.success() {
-        return Err(format!(
-            "mdfind failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
-    }
+/// Newest modification time of any source file in a project
+///
+/// Deletable artifacts and VCS metadata are skipped, so rebuilding a project
+/// doesn't count as working on it. Very large trees are only sampled.
+pub fn project_last_activity(project_root: &Path) -> Option<SystemTime> {
+    WalkDir::new(project_root)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.path() == project_root {
+                return true;
+            }
 
-    Ok(String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|s| PathBuf::from(s.trim()))
-        .filter(|p| p.exists())
-        .collect())
+            let is_vcs = e.file_type().is_dir()
+                && e.file_name().to_str().map(|n| VCS_DIRS.contains(&n)).unwrap_or(false);
+
+            !is_vcs && is_deletable(e.path()).is_none()
+        })
+        .take(ACTIVITY_WALK_LIMIT)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok().and_then(|m| m.modified().ok()))
+        .max()
 }
 
+/// Resolve the timestamp each item should be aged by
+///
+/// Returns one timestamp per item, in order. Falls back to the item's own
+/// `last_modified` when the requested timestamp can't be read. `roots` are
+/// the scan roots, which bound the search for each item's project.
+pub fn resolve_item_ages(items: &[DeletableItem], source: AgeSource, roots: &[PathBuf]) -> Vec<SystemTime> {
+    match source {
+        AgeSource::Mtime => items.iter().map(|item| item.last_modified).collect(),
+        AgeSource::Atime => items
+            .par_iter()
+            .map(|item| {
+                fs::metadata(&item.path)
+                    .and_then(|m| m.accessed())
+                    .unwrap_or(item.last_modified)
+            })
+            .collect(),
+        AgeSource::Project => {
+            let roots: Vec<PathBuf> = items.par_iter().map(|item| find_project_root(&item.path, roots)).collect();
+
+            let mut unique_roots = roots.clone();
+            unique_roots.sort();
+            unique_roots.dedup();
+
+            let activity: HashMap<PathBuf, SystemTime> = unique_roots
+                .into_par_iter()
+                .filter_map(|root| project_last_activity(&root).map(|time| (root, time)))
+                .collect();
+
+            items
+                .iter()
+                .zip(roots)
+                .map(|(item, root)| activity.get(&root).copied().unwrap_or(item.last_modified))
+                .collect()
+        }
+    }
+}
+
+// ============================================================================
+// System Indexing Functions (Spotlight on macOS, locate on Linux)
+// ============================================================================
+
+/// Name of the system index `--index` uses on this platform
 #[cfg(target_os = "macos")]
-fn scan_directory_macos(path: &Path, _max_depth: usize, quiet: bool) -> Result<Vec<DeletableItem>, String> {
-    let pb = if quiet {
-        ProgressBar::hidden()
-    } else {
-        ProgressBar::new_spinner()
-    };
+pub const INDEX_BACKEND: &str = "Spotlight";
+#[cfg(target_os = "linux")]
+pub const INDEX_BACKEND: &str = "locate";
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub const INDEX_BACKEND: &str = "system index";
+
+/// Validate paths reported by a system index
+///
+/// Keeps candidates inside the search path that the detectors accept and
+/// that aren't nested inside another deletable directory.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn validate_indexed(base_path: &Path, mut candidates: Vec<PathBuf>) -> Vec<(PathBuf, Category)> {
+    let canonical_base = base_path.canonicalize().unwrap_or_else(|_| base_path.to_path_buf());
+
+    candidates.sort();
+    candidates.dedup();
+
+    candidates
+        .into_par_iter()
+        .filter_map(|p| {
+            // Must be within the search path
+            let base = if p.starts_with(base_path) {
+                base_path
+            } else if p.starts_with(&canonical_base) {
+                canonical_base.as_path()
+            } else {
+                return None;
+            };
 
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} Querying Spotlight index... [{elapsed_precise}] {msg}")
-            .unwrap()
-            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
-    );
+            // The registry resolves ambiguous names (vendor, target, build)
+            let detected_category = is_deletable(&p)?;
 
-    // Parallel queries to Spotlight
-    let queries = vec![
-        ("target", Category::RustTarget),
-        ("node_modules", Category::NodeModules),
-        ("__pycache__", Category::PythonCache),
-        ("build", Category::BuildCache),
-        (".gradle", Category::GradleBuild),
-        ("venv", Category::PythonCache),
-        (".venv", Category::PythonCache),
-        ("dist", Category::BuildCache),
-        ("vendor", Category::GoVendor), // Will match both PHP and Go, filtered later
-        ("CMakeFiles", Category::CCache),
-        ("bin", Category::DotNetBuild),
-        ("obj", Category::DotNetBuild),
-        (".build", Category::SwiftBuild),
-        ("DerivedData", Category::SwiftBuild),
-        (".idea", Category::IDECache),
-        (".vscode", Category::IDECache),
-        (".vs", Category::IDECache),
-        (".bundle", Category::RubyGems),
-        (".DS_Store", Category::OSJunk),
-        ("Thumbs.db", Category::OSJunk),
-        (".sass-cache", Category::TempFiles),
-        (".parcel-cache", Category::TempFiles),
-    ];
-
-    let base_path = path.to_path_buf();
-    let canonical_base = base_path.canonicalize().unwrap_or_else(|_| base_path.clone());
-
-    let pending_items: Vec<(PathBuf, Category)> = queries
-        .par_iter()
-        .flat_map(|(name, category)| {
-            if !quiet {
-                pb.set_message(format!("Finding {}...", name));
-                pb.tick();
+            // Skip if this is inside another deletable directory (avoid nested)
+            let nested = p
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| *dir != base && dir.starts_with(base))
+                .any(|dir| is_deletable(dir).is_some());
+
+            if nested {
+                return None;
             }
 
-            find_with_mdfind(&base_path, &format!("kMDItemFSName == '{}'", name))
-                .unwrap_or_default()
-                .into_iter()
-                .filter_map(|p| {
-                    // Must be within the search path
-                    if !p.starts_with(&base_path) && !p.starts_with(&canonical_base) {
-                        return None;
-                    }
-
-                    // Must be a directory
-                    if !p.is_dir() {
-                        return None;
-                    }
-
-                    // Get the actual category using is_deletable() for accurate detection
-                    let detected_category = is_deletable(&p)?;
-
-                    // Apply category-specific filters
-                    match category {
-                        Category::RustTarget => {
-                            // Check if it's actually a Cargo target directory
-                            if detected_category != Category::RustTarget {
-                                return None;
-                            }
-                        }
-                        Category::GoVendor => {
-                            // For vendor dirs, accept PHP, Go, and Ruby
-                            // (they all match "vendor" name in Spotlight)
-                            if detected_category != Category::GoVendor
-                                && detected_category != Category::PHPVendor
-                                && detected_category != Category::RubyGems {
-                                return None;
-                            }
-                        }
-                        _ => {
-                            // For other categories, just verify they match
-                            if detected_category != *category {
-                                return None;
-                            }
-                        }
-                    }
-
-                    // Skip if this is inside another deletable directory (avoid nested)
-                    // Check parent directories to see if any are deletable
-                    if let Some(parent) = p.parent() {
-                        let mut current = parent;
-                        while current != &base_path && current.starts_with(&base_path) {
-                            if is_deletable(current).is_some() {
-                                // This is nested inside another deletable dir, skip it
-                                return None;
-                            }
-                            if let Some(next_parent) = current.parent() {
-                                current = next_parent;
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-
-                    // Return with the DETECTED category, not the query category!
-                    Some((p, detected_category))
-                })
-                .collect::<Vec<_>>()
+            Some((p, detected_category))
         })
-        .collect();
-
-    pb.finish_with_message(format!("✓ Spotlight found {} deletable directories. Calculating sizes...", pending_items.len()));
+        .collect()
+}
 
-    // Calculate sizes in parallel
+/// Calculate sizes and build scan results for validated index hits
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn size_indexed(pending_items: Vec<(PathBuf, Category)>, quiet: bool) -> Vec<DeletableItem> {
     let size_pb = Arc::new(Mutex::new(if quiet {
         ProgressBar::hidden()
     } else {
@@ -576,7 +572,74 @@ fn scan_directory_macos(path: &Path, _max_depth: usize, quiet: bool) -> Result<V
 
     size_pb.lock().unwrap().finish_and_clear();
 
-    Ok(items)
+    items
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn index_spinner(quiet: bool, template: &str) -> ProgressBar {
+    let pb = if quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new_spinner()
+    };
+
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template(template)
+            .unwrap()
+            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+    );
+
+    pb
+}
+
+#[cfg(target_os = "macos")]
+fn find_with_mdfind(base_path: &Path, query: &str) -> Result<Vec<PathBuf>, String> {
+    let output = Command::new("mdfind")
+        .arg("-onlyin")
+        .arg(base_path)
+        .arg(query)
+        .output()
+        .map_err(|e| format!("Failed to execute mdfind: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "mdfind failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|s| PathBuf::from(s.trim()))
+        .filter(|p| p.exists())
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+fn scan_directory_macos(path: &Path, _max_depth: usize, quiet: bool) -> Result<Vec<DeletableItem>, String> {
+    let pb = index_spinner(quiet, "{spinner:.cyan} Querying Spotlight index... [{elapsed_precise}] {msg}");
+
+    // Parallel queries to Spotlight, one per name the detectors know about
+    let queries = registry().indexed_names();
+
+    let candidates: Vec<PathBuf> = queries
+        .par_iter()
+        .flat_map(|name| {
+            if !quiet {
+                pb.set_message(format!("Finding {}...", name));
+                pb.tick();
+            }
+
+            find_with_mdfind(path, &format!("kMDItemFSName == '{}'", name)).unwrap_or_default()
+        })
+        .collect();
+
+    let pending_items = validate_indexed(path, candidates);
+
+    pb.finish_with_message(format!("✓ Spotlight found {} deletable directories. Calculating sizes...", pending_items.len()));
+
+    Ok(size_indexed(pending_items, quiet))
 }
 
 #[cfg(target_os = "macos")]
@@ -593,7 +656,121 @@ pub fn try_indexed_scan(path: &Path, max_depth: usize, quiet: bool) -> Result<Ve
     scan_directory_macos(path, max_depth, quiet)
 }
 
-#[cfg(not(target_os = "macos"))]
+/// Databases are considered stale once they're older than this
+#[cfg(target_os = "linux")]
+const LOCATE_STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Well-known locate database locations (plocate, mlocate, findutils)
+#[cfg(target_os = "linux")]
+const LOCATE_DATABASES: &[&str] = &[
+    "/var/lib/plocate/plocate.db",
+    "/var/lib/mlocate/mlocate.db",
+    "/var/cache/locate/locatedb",
+    "/var/lib/locate/locatedb",
+];
+
+/// First locate implementation found on `PATH`
+#[cfg(target_os = "linux")]
+fn find_locate_binary() -> Option<&'static str> {
+    ["plocate", "locate"].into_iter().find(|bin| {
+        Command::new(bin)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    })
+}
+
+/// Time since the locate database was last updated
+///
+/// Honors `LOCATE_PATH` before the distribution defaults.
+#[cfg(target_os = "linux")]
+fn locate_database_age() -> Option<Duration> {
+    let from_env: Vec<PathBuf> = std::env::var_os("LOCATE_PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+
+    from_env
+        .into_iter()
+        .chain(LOCATE_DATABASES.iter().map(PathBuf::from))
+        .find_map(|db| fs::metadata(db).ok()?.modified().ok())
+        .and_then(|updated| SystemTime::now().duration_since(updated).ok())
+}
+
+/// Query locate for entries whose base name is exactly one of `names`
+#[cfg(target_os = "linux")]
+fn find_with_locate(binary: &str, names: &[String]) -> Result<Vec<PathBuf>, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    // A leading backslash makes locate match the whole base name instead of *name*
+    let output = Command::new(binary)
+        .args(["-0", "--existing", "--basename"])
+        .args(names.iter().map(|name| format!("\\{}", name)))
+        .output()
+        .map_err(|e| format!("Failed to execute {}: {}", binary, e))?;
+
+    // Exit code 1 with no error output just means nothing matched
+    let nothing_matched = output.status.code() == Some(1) && output.stderr.is_empty();
+    if !output.status.success() && !nothing_matched {
+        return Err(format!(
+            "{} failed: {}",
+            binary,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(output
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| PathBuf::from(std::ffi::OsStr::from_bytes(s)))
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn scan_directory_linux(path: &Path, binary: &str, quiet: bool) -> Result<Vec<DeletableItem>, String> {
+    if let Some(age) = locate_database_age().filter(|age| *age > LOCATE_STALE_AFTER) {
+        println_unless_quiet!(
+            quiet,
+            "{}",
+            format!(
+                "⚠ The locate database was last updated {} ago - recent items may be missing (run `sudo updatedb`)",
+                format_age(age)
+            )
+            .yellow()
+        );
+    }
+
+    let pb = index_spinner(quiet, "{spinner:.cyan} Querying locate database... [{elapsed_precise}] {msg}");
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let candidates = find_with_locate(binary, &registry().indexed_names())?;
+    let pending_items = validate_indexed(path, candidates);
+
+    pb.finish_with_message(format!("✓ locate found {} deletable directories. Calculating sizes...", pending_items.len()));
+
+    Ok(size_indexed(pending_items, quiet))
+}
+
+#[cfg(target_os = "linux")]
+fn format_age(age: Duration) -> String {
+    let hours = age.as_secs() / 3600;
+    if hours < 48 {
+        format!("{} hours", hours)
+    } else {
+        format!("{} days", hours / 24)
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn try_indexed_scan(path: &Path, _max_depth: usize, quiet: bool) -> Result<Vec<DeletableItem>, String> {
+    let binary = find_locate_binary().ok_or("plocate/locate not available")?;
+    scan_directory_linux(path, binary, quiet)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 pub fn try_indexed_scan(_path: &Path, _max_depth: usize, _quiet: bool) -> Result<Vec<DeletableItem>, String> {
-    Err("System indexing only supported on macOS currently".to_string())
+    Err("System indexing is only supported on macOS and Linux".to_string())
 }
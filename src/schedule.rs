@@ -0,0 +1,317 @@
+//! # Schedule Module
+//!
+//! Installs a recurring `gigabroom clean` for `gigabroom schedule`.
+//!
+//! With a systemd user session this is a oneshot service plus a timer in
+//! `~/.config/systemd/user`; otherwise it's a line in the user's crontab,
+//! tagged so it can be found and replaced later. Either way the job runs
+//! `clean --yes --quiet` on fixed roots, so deletions end up in the journal
+//! and only errors end up in the logs.
+
+use crate::utils::xdg_config_home;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const UNIT_NAME: &str = "gigabroom-clean";
+
+/// Comment marking the crontab line gigabroom manages
+const CRON_TAG: &str = "# gigabroom-schedule";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl Frequency {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Frequency::Hourly => "hourly",
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+        }
+    }
+}
+
+/// Where a schedule lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Systemd,
+    Cron,
+}
+
+impl Backend {
+    /// systemd if a user manager is running, cron otherwise
+    pub fn detect() -> Self {
+        let systemd = Command::new("systemctl")
+            .args(["--user", "show-environment"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+
+        if systemd {
+            Backend::Systemd
+        } else {
+            Backend::Cron
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Backend::Systemd => "systemd user timer",
+            Backend::Cron => "crontab",
+        }
+    }
+}
+
+/// A recurring cleanup
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub frequency: Frequency,
+    /// One `gigabroom clean ...` invocation per root, as argument lists
+    pub commands: Vec<Vec<String>>,
+}
+
+fn unit_dir() -> PathBuf {
+    xdg_config_home().join("systemd/user")
+}
+
+fn service_path() -> PathBuf {
+    unit_dir().join(format!("{}.service", UNIT_NAME))
+}
+
+fn timer_path() -> PathBuf {
+    unit_dir().join(format!("{}.timer", UNIT_NAME))
+}
+
+/// Quote an argument for a systemd `ExecStart=` line
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if !escaped.is_empty() && escaped.chars().all(|c| c.is_ascii_alphanumeric() || "/._-=:+,@%$".contains(c)) {
+        return escaped;
+    }
+    format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote an argument for a crontab command (a shell line where `%` is special)
+fn cron_quote(arg: &str) -> String {
+    let quoted = if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "/._-=:+,@".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    };
+    quoted.replace('%', "\\%")
+}
+
+fn render_service(schedule: &Schedule) -> String {
+    let mut unit = String::from(
+        "[Unit]\nDescription=Clean build artifacts with gigabroom\n\n[Service]\nType=oneshot\nNice=10\nIOSchedulingClass=idle\n",
+    );
+    for command in &schedule.commands {
+        let line: Vec<String> = command.iter().map(|arg| systemd_quote(arg)).collect();
+        unit.push_str(&format!("ExecStart={}\n", line.join(" ")));
+    }
+    unit
+}
+
+fn render_timer(schedule: &Schedule) -> String {
+    format!(
+        "[Unit]\nDescription=Run gigabroom clean {}\n\n[Timer]\nOnCalendar={}\nPersistent=true\nRandomizedDelaySec=15min\n\n[Install]\nWantedBy=timers.target\n",
+        schedule.frequency.name(),
+        schedule.frequency.name()
+    )
+}
+
+fn render_cron_line(schedule: &Schedule) -> String {
+    let commands: Vec<String> = schedule
+        .commands
+        .iter()
+        .map(|command| command.iter().map(|arg| cron_quote(arg)).collect::<Vec<_>>().join(" "))
+        .collect();
+    format!("@{} {} {}", schedule.frequency.name(), commands.join("; "), CRON_TAG)
+}
+
+/// A crontab with gigabroom's line replaced by `line` (or just removed)
+fn replace_cron_line(crontab: &str, line: Option<&str>) -> String {
+    let mut lines: Vec<&str> = crontab.lines().filter(|l| !l.ends_with(CRON_TAG)).collect();
+    lines.extend(line);
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    updated
+}
+
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("systemctl: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "systemctl --user {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// The current crontab; having none yet is the same as an empty one
+fn read_crontab() -> Result<String, String> {
+    let output = Command::new("crontab").arg("-l").output().map_err(|e| format!("crontab: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Ok(String::new())
+    }
+}
+
+fn write_crontab(crontab: &str) -> Result<(), String> {
+    let mut child = Command::new("crontab")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("crontab: {}", e))?;
+
+    child
+        .stdin
+        .take()
+        .ok_or("crontab: no stdin")?
+        .write_all(crontab.as_bytes())
+        .map_err(|e| format!("crontab: {}", e))?;
+
+    let output = child.wait_with_output().map_err(|e| format!("crontab: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("crontab: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Install (or replace) the schedule, returning where it was written
+pub fn install(schedule: &Schedule, backend: Backend) -> Result<String, String> {
+    match backend {
+        Backend::Systemd => {
+            fs::create_dir_all(unit_dir()).map_err(|e| format!("{}: {}", unit_dir().display(), e))?;
+            for (path, contents) in [(service_path(), render_service(schedule)), (timer_path(), render_timer(schedule))] {
+                fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+
+            systemctl(&["daemon-reload"])?;
+            systemctl(&["enable", "--now", &format!("{}.timer", UNIT_NAME)])?;
+            Ok(timer_path().display().to_string())
+        }
+        Backend::Cron => {
+            let crontab = read_crontab()?;
+            write_crontab(&replace_cron_line(&crontab, Some(&render_cron_line(schedule))))?;
+            Ok("your crontab".to_string())
+        }
+    }
+}
+
+/// What's installed, as lines to show; empty if nothing is
+pub fn status() -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+
+    if timer_path().exists() {
+        lines.push(format!("systemd user timer: {}", timer_path().display()));
+
+        let service = fs::read_to_string(service_path()).unwrap_or_default();
+        lines.extend(service.lines().filter_map(|l| l.strip_prefix("ExecStart=")).map(|c| format!("  runs: {}", c)));
+
+        let timer = fs::read_to_string(timer_path()).unwrap_or_default();
+        lines.extend(timer.lines().filter_map(|l| l.strip_prefix("OnCalendar=")).map(|c| format!("  when: {}", c)));
+
+        match systemctl(&["list-timers", "--all", "--no-legend", &format!("{}.timer", UNIT_NAME)]) {
+            Ok(listing) if !listing.trim().is_empty() => lines.push(format!("  next: {}", listing.trim())),
+            Ok(_) => lines.push("  not active (systemctl --user enable --now gigabroom-clean.timer)".to_string()),
+            Err(e) => lines.push(format!("  state unknown: {}", e)),
+        }
+    }
+
+    if let Ok(crontab) = read_crontab() {
+        for line in crontab.lines().filter(|l| l.ends_with(CRON_TAG)) {
+            lines.push("crontab:".to_string());
+            lines.push(format!("  {}", line.trim_end_matches(CRON_TAG).trim_end()));
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Remove the schedule from every backend; returns whether anything was installed
+pub fn remove() -> Result<bool, String> {
+    let mut removed = false;
+
+    if timer_path().exists() || service_path().exists() {
+        // The timer may never have been enabled, or the user manager may be gone
+        let _ = systemctl(&["disable", "--now", &format!("{}.timer", UNIT_NAME)]);
+        for path in [timer_path(), service_path()] {
+            if path.exists() {
+                fs::remove_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+        let _ = systemctl(&["daemon-reload"]);
+        removed = true;
+    }
+
+    if let Ok(crontab) = read_crontab() {
+        if crontab.lines().any(|l| l.ends_with(CRON_TAG)) {
+            write_crontab(&replace_cron_line(&crontab, None))?;
+            removed = true;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> Schedule {
+        Schedule {
+            frequency: Frequency::Daily,
+            commands: vec![vec![
+                "/usr/bin/gigabroom".to_string(),
+                "clean".to_string(),
+                "/home/me/My Projects".to_string(),
+                "--older-than".to_string(),
+                "30d".to_string(),
+            ]],
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert!(render_service(&schedule())
+            .contains("ExecStart=/usr/bin/gigabroom clean \"/home/me/My Projects\" --older-than 30d\n"));
+        assert!(render_timer(&schedule()).contains("OnCalendar=daily\n"));
+        assert_eq!(
+            render_cron_line(&schedule()),
+            "@daily /usr/bin/gigabroom clean '/home/me/My Projects' --older-than 30d # gigabroom-schedule"
+        );
+        assert_eq!(systemd_quote("50%"), "50%%");
+        assert_eq!(cron_quote("50%"), "'50\\%'");
+    }
+
+    #[test]
+    fn test_replace_cron_line() {
+        let crontab = "MAILTO=me\n@daily old # gigabroom-schedule\n0 * * * * backup\n";
+        assert_eq!(
+            replace_cron_line(crontab, Some("@hourly new # gigabroom-schedule")),
+            "MAILTO=me\n0 * * * * backup\n@hourly new # gigabroom-schedule\n"
+        );
+        assert_eq!(replace_cron_line(crontab, None), "MAILTO=me\n0 * * * * backup\n");
+        assert_eq!(replace_cron_line("", None), "");
+    }
+}
@@ -0,0 +1,251 @@
+//! # Trash Module
+//!
+//! Moves items into the freedesktop.org trash instead of deleting them.
+//!
+//! Items on the home filesystem go to `$XDG_DATA_HOME/Trash`; items on other
+//! filesystems go to the trash at the top of their own filesystem
+//! (`$topdir/.Trash/$uid` or `$topdir/.Trash-$uid`), so trashing is always a
+//! rename. Every item gets a `.trashinfo` file, so desktop file managers can
+//! restore it too. gigabroom also logs what it trashed, which is what
+//! `gigabroom restore` offers to bring back.
+
+use crate::types::{Category, DeletableItem};
+use crate::utils::{absolute_path, create_private_dir, data_dir, device_of, mount_top, xdg_data_home};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// An item gigabroom moved to the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItem {
+    /// Where the item lived before it was trashed
+    pub original: PathBuf,
+    /// The item inside the trash's `files/` directory
+    pub trashed_path: PathBuf,
+    /// Its `.trashinfo` file
+    pub info_path: PathBuf,
+    pub trashed_at: DateTime<Local>,
+    pub size: u64,
+    pub category: Category,
+}
+
+/// Returns the path to gigabroom's log of trashed items
+fn log_path() -> PathBuf {
+    data_dir().join("trash.json")
+}
+
+fn load_log() -> Vec<TrashedItem> {
+    fs::read_to_string(log_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_log(items: &[TrashedItem]) -> Result<(), String> {
+    let path = log_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(items).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Add trashed items to the log
+pub fn record(trashed: &[TrashedItem]) -> Result<(), String> {
+    if trashed.is_empty() {
+        return Ok(());
+    }
+    let mut log = load_log();
+    log.extend_from_slice(trashed);
+    save_log(&log)
+}
+
+/// Items gigabroom trashed that are still in the trash, oldest first
+///
+/// Entries whose files are gone (trash emptied, restored elsewhere) are
+/// dropped from the log.
+pub fn trashed_items() -> Vec<TrashedItem> {
+    let log = load_log();
+    let total = log.len();
+
+    let mut present: Vec<TrashedItem> = log
+        .into_iter()
+        .filter(|item| fs::symlink_metadata(&item.trashed_path).is_ok())
+        .collect();
+
+    if present.len() != total {
+        let _ = save_log(&present);
+    }
+
+    present.sort_by_key(|item| item.trashed_at);
+    present
+}
+
+/// Percent-encode a path for the `Path=` key of a `.trashinfo` file
+fn encode_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut encoded = String::new();
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// A trash directory, holding `files/` and `info/`
+struct TrashDir {
+    root: PathBuf,
+    /// Top of the filesystem for `$topdir` trashes; `Path=` is relative to it
+    topdir: Option<PathBuf>,
+}
+
+/// Pick the trash on the same filesystem as `path`
+fn trash_dir_for(path: &Path) -> Result<TrashDir, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let dev = device_of(path).ok_or_else(|| format!("{}: not found", path.display()))?;
+
+    let home_trash = xdg_data_home().join("Trash");
+    if create_private_dir(&home_trash).is_ok() && device_of(&home_trash) == Some(dev) {
+        return Ok(TrashDir {
+            root: home_trash,
+            topdir: None,
+        });
+    }
+
+    let topdir = mount_top(path, dev);
+    let uid = unsafe { libc::getuid() };
+
+    // An admin-created .Trash must be a real, sticky directory to be trusted
+    let shared = topdir.join(".Trash");
+    let shared_ok = fs::symlink_metadata(&shared)
+        .map(|m| m.is_dir() && m.mode() & 0o1000 != 0)
+        .unwrap_or(false);
+
+    let candidates = [
+        shared_ok.then(|| shared.join(uid.to_string())),
+        Some(topdir.join(format!(".Trash-{}", uid))),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|root| create_private_dir(root).is_ok() && device_of(root) == Some(dev))
+        .map(|root| TrashDir {
+            root,
+            topdir: Some(topdir.clone()),
+        })
+        .ok_or_else(|| format!("no writable trash on the filesystem of {}", path.display()))
+}
+
+/// Move an item into the trash
+pub fn move_to_trash(item: &DeletableItem) -> Result<TrashedItem, String> {
+    let original = absolute_path(&item.path)?;
+    let trash = trash_dir_for(&original)?;
+
+    let files_dir = trash.root.join("files");
+    let info_dir = trash.root.join("info");
+    for dir in [&files_dir, &info_dir] {
+        create_private_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+
+    let recorded_path = match &trash.topdir {
+        Some(topdir) => original.strip_prefix(topdir).unwrap_or(&original).to_path_buf(),
+        None => original.clone(),
+    };
+
+    let trashed_at = Local::now();
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(&recorded_path),
+        trashed_at.format("%Y-%m-%dT%H:%M:%S")
+    );
+
+    let base_name = original
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // Claim a unique name by creating the .trashinfo file exclusively
+    for n in 1u32.. {
+        let name = if n == 1 {
+            base_name.clone()
+        } else {
+            format!("{}.{}", base_name, n)
+        };
+        let info_path = info_dir.join(format!("{}.trashinfo", name));
+        let trashed_path = files_dir.join(&name);
+
+        let mut file = match fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("{}: {}", info_path.display(), e)),
+        };
+
+        if fs::symlink_metadata(&trashed_path).is_ok() {
+            // Orphaned file without info: leave it alone and pick another name
+            let _ = fs::remove_file(&info_path);
+            continue;
+        }
+
+        let moved = file
+            .write_all(info.as_bytes())
+            .and_then(|_| fs::rename(&original, &trashed_path));
+
+        return match moved {
+            Ok(()) => Ok(TrashedItem {
+                original,
+                trashed_path,
+                info_path,
+                trashed_at,
+                size: item.size,
+                category: item.category,
+            }),
+            Err(e) => {
+                let _ = fs::remove_file(&info_path);
+                Err(e.to_string())
+            }
+        };
+    }
+
+    unreachable!("ran out of trash names")
+}
+
+/// Move a trashed item back to where it came from
+pub fn restore(item: &TrashedItem) -> Result<(), String> {
+    if fs::symlink_metadata(&item.original).is_ok() {
+        return Err(format!("{} already exists", item.original.display()));
+    }
+
+    if let Some(parent) = item.original.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+
+    fs::rename(&item.trashed_path, &item.original).map_err(|e| e.to_string())?;
+    let _ = fs::remove_file(&item.info_path);
+
+    let log: Vec<TrashedItem> = load_log()
+        .into_iter()
+        .filter(|entry| entry.trashed_path != item.trashed_path)
+        .collect();
+    save_log(&log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path(Path::new("/home/me/app/target")), "/home/me/app/target");
+        assert_eq!(encode_path(Path::new("/tmp/my dir/.venv")), "/tmp/my%20dir/.venv");
+        assert_eq!(encode_path(Path::new("/tmp/ü")), "/tmp/%C3%BC");
+    }
+}
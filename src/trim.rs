@@ -0,0 +1,359 @@
+//! # Trim Module
+//!
+//! Prunes stale data inside Rust `target/` directories instead of deleting
+//! them outright (in the spirit of cargo-sweep).
+//!
+//! Within every profile directory (`target/debug`, `target/<triple>/release`,
+//! ...) a build unit is identified by its `<crate>-<hash>` stem, shared by
+//! `.fingerprint/`, `deps/` and `build/`. A unit is trimmed when it hasn't
+//! been used for longer than the cutoff, or when its fingerprint was written
+//! by a different rustc than the one the project builds with (as rustup picks
+//! it from a `rust-toolchain.toml`). Old incremental compilation sessions are
+//! pruned by age. Everything else is kept, so the next build only recompiles
+//! what was actually removed.
+
+use crate::scanner::calculate_dir_size_parallel;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// Outcome of trimming one target directory
+#[derive(Debug, Default, Clone)]
+pub struct TrimReport {
+    /// Bytes removed (or that would be removed in a dry run)
+    pub trimmed_bytes: u64,
+    /// Files and directories removed
+    pub removed_entries: usize,
+    /// Build units removed because another toolchain built them
+    pub stale_toolchain_units: usize,
+    /// Build units removed because they weren't used within the cutoff
+    pub stale_age_units: usize,
+    /// Entries that could not be removed
+    pub errors: Vec<String>,
+}
+
+/// A `<crate>-<hash>` build unit found in `.fingerprint/`
+struct BuildUnit {
+    stem: String,
+    last_used: SystemTime,
+    rustc: Option<u64>,
+}
+
+/// Output of `rustc -vV` for the toolchain a directory builds with
+///
+/// Runs from `dir`, so rustup honors a `rust-toolchain.toml` pinned there.
+pub fn current_rustc_version(dir: &Path) -> Option<String> {
+    let output = Command::new("rustc").arg("-vV").current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// `rustc -vV` of each workspace root, asked once per root
+#[derive(Default)]
+pub struct RustcVersions(HashMap<PathBuf, Option<String>>);
+
+impl RustcVersions {
+    pub fn for_root(&mut self, root: &Path) -> Option<&str> {
+        self.0
+            .entry(root.to_path_buf())
+            .or_insert_with(|| current_rustc_version(root))
+            .as_deref()
+    }
+}
+
+/// Check whether the last build in a target used the given rustc
+///
+/// Cargo caches `rustc -vV` output in `.rustc_info.json` on every build.
+fn last_build_used(target: &Path, rustc_version: &str) -> bool {
+    let Some(first_line) = rustc_version.lines().next() else {
+        return false;
+    };
+
+    fs::read_to_string(target.join(".rustc_info.json"))
+        .ok()
+        .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+        .and_then(|info| {
+            info.get("outputs")?.as_object().map(|outputs| {
+                outputs.values().any(|output| {
+                    output
+                        .get("stdout")
+                        .and_then(|s| s.as_str())
+                        .map(|s| s.lines().next() == Some(first_line))
+                        .unwrap_or(false)
+                })
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Profile directories (those holding a `.fingerprint/`) within a target
+fn profile_dirs(target: &Path) -> Vec<PathBuf> {
+    let mut profiles = Vec::new();
+    let Ok(entries) = fs::read_dir(target) else {
+        return profiles;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.join(".fingerprint").is_dir() {
+            profiles.push(path);
+        } else if let Ok(nested) = fs::read_dir(&path) {
+            // Cross-compiled profiles live one level deeper: target/<triple>/<profile>
+            profiles.extend(
+                nested
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.join(".fingerprint").is_dir()),
+            );
+        }
+    }
+
+    profiles
+}
+
+/// Newest modification or access time of any entry below a path
+fn last_used(path: &Path) -> SystemTime {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .flat_map(|m| [m.modified().ok(), m.accessed().ok()])
+        .flatten()
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Read a file without bumping its access time where the OS allows it
+///
+/// Access times are how we tell which units cargo still uses, so looking at
+/// a fingerprint must not make it look freshly used.
+#[cfg(target_os = "linux")]
+fn read_preserving_atime(path: &Path) -> Option<String> {
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NOATIME)
+        .open(path)
+        .or_else(|_| fs::File::open(path))
+        .ok()?;
+
+    let mut data = String::new();
+    file.read_to_string(&mut data).ok()?;
+    Some(data)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_preserving_atime(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+/// Read every build unit in a profile's `.fingerprint/` directory
+fn build_units(profile: &Path) -> Vec<BuildUnit> {
+    let Ok(entries) = fs::read_dir(profile.join(".fingerprint")) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|entry| {
+            let stem = entry.file_name().to_str()?.to_string();
+
+            // Take the timestamp before reading anything inside the unit
+            let last_used = last_used(&entry.path());
+
+            let rustc = fs::read_dir(entry.path())
+                .ok()?
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().extension().map(|x| x == "json").unwrap_or(false))
+                .find_map(|e| {
+                    let data = read_preserving_atime(&e.path())?;
+                    serde_json::from_str::<Value>(&data).ok()?.get("rustc")?.as_u64()
+                });
+
+            Some(BuildUnit {
+                stem,
+                last_used,
+                rustc,
+            })
+        })
+        .collect()
+}
+
+/// Check whether a `deps/` entry belongs to a build unit
+///
+/// Fingerprints keep the package's dashes, while `deps/` names use the crate
+/// name, so `proc-macro2-<hash>` owns `libproc_macro2-<hash>.rlib`.
+fn belongs_to_unit(file_name: &str, stem: &str) -> bool {
+    let stem = match stem.rsplit_once('-') {
+        Some((name, hash)) => format!("{}-{}", name.replace('-', "_"), hash),
+        None => stem.to_string(),
+    };
+    let stem = stem.as_str();
+    let name = file_name.strip_prefix("lib").filter(|n| n.starts_with(stem)).unwrap_or(file_name);
+    name == stem || name.strip_prefix(stem).map(|rest| rest.starts_with('.')).unwrap_or(false)
+}
+
+/// Remove a file or directory, accounting for it in the report
+fn remove_entry(path: &Path, dry_run: bool, report: &mut TrimReport) {
+    let size = if path.is_dir() {
+        calculate_dir_size_parallel(path)
+    } else {
+        fs::symlink_metadata(path).map(|m| m.len()).unwrap_or(0)
+    };
+
+    let result = if dry_run {
+        Ok(())
+    } else if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+
+    match result {
+        Ok(()) => {
+            report.trimmed_bytes += size;
+            report.removed_entries += 1;
+        }
+        Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Prune incremental sessions older than the cutoff
+fn trim_incremental(profile: &Path, cutoff: SystemTime, dry_run: bool, report: &mut TrimReport) {
+    let Ok(crates) = fs::read_dir(profile.join("incremental")) else {
+        return;
+    };
+
+    for crate_dir in crates.filter_map(|e| e.ok()).map(|e| e.path()) {
+        let Ok(sessions) = fs::read_dir(&crate_dir) else {
+            continue;
+        };
+
+        let sessions: Vec<PathBuf> = sessions.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        let stale: Vec<&PathBuf> = sessions.iter().filter(|s| last_used(s) < cutoff).collect();
+
+        if !sessions.is_empty() && stale.len() == sessions.len() {
+            remove_entry(&crate_dir, dry_run, report);
+        } else {
+            for session in stale {
+                remove_entry(session, dry_run, report);
+            }
+        }
+    }
+}
+
+/// Trim one Rust target directory
+///
+/// Removes build units unused for longer than `max_age`, units built by a
+/// rustc other than `rustc_version` (when known), and stale incremental
+/// sessions. Other toolchains are only told apart when the last build used
+/// `rustc_version`; otherwise units are trimmed by age alone. With `dry_run`
+/// nothing is removed but the report is filled in.
+pub fn trim_rust_target(
+    target: &Path,
+    max_age: Duration,
+    rustc_version: Option<&str>,
+    dry_run: bool,
+) -> TrimReport {
+    let mut report = TrimReport::default();
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let current_toolchain_built_last = rustc_version.is_some_and(|v| last_build_used(target, v));
+
+    for profile in profile_dirs(target) {
+        let units = build_units(&profile);
+
+        // The most recently used unit tells us which rustc hash the current
+        // toolchain produces, as long as the last build used that toolchain
+        let current_hash = if current_toolchain_built_last {
+            units.iter().max_by_key(|u| u.last_used).and_then(|u| u.rustc)
+        } else {
+            None
+        };
+
+        let mut stale_stems: HashSet<&str> = HashSet::new();
+        for unit in &units {
+            let other_toolchain = current_hash.is_some() && unit.rustc.is_some() && unit.rustc != current_hash;
+
+            if other_toolchain {
+                report.stale_toolchain_units += 1;
+                stale_stems.insert(&unit.stem);
+            } else if unit.last_used < cutoff {
+                report.stale_age_units += 1;
+                stale_stems.insert(&unit.stem);
+            }
+        }
+
+        for stem in &stale_stems {
+            remove_entry(&profile.join(".fingerprint").join(stem), dry_run, &mut report);
+
+            let build_dir = profile.join("build").join(stem);
+            if build_dir.exists() {
+                remove_entry(&build_dir, dry_run, &mut report);
+            }
+        }
+
+        if let Ok(deps) = fs::read_dir(profile.join("deps")) {
+            for entry in deps.filter_map(|e| e.ok()) {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else { continue };
+                if stale_stems.iter().any(|stem| belongs_to_unit(name, stem)) {
+                    remove_entry(&entry.path(), dry_run, &mut report);
+                }
+            }
+        }
+
+        trim_incremental(&profile, cutoff, dry_run, &mut report);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_belongs_to_unit() {
+        assert!(belongs_to_unit("libserde-1a2b.rlib", "serde-1a2b"));
+        assert!(belongs_to_unit("libserde-1a2b.rmeta", "serde-1a2b"));
+        assert!(belongs_to_unit("serde-1a2b.d", "serde-1a2b"));
+        assert!(belongs_to_unit("gigabroom-1a2b", "gigabroom-1a2b"));
+        assert!(!belongs_to_unit("libserde_json-1a2b.rlib", "serde-1a2b"));
+        assert!(!belongs_to_unit("serde-1a2bc.d", "serde-1a2b"));
+        assert!(!belongs_to_unit("liblibc-99.rlib", "lib-99"));
+        assert!(belongs_to_unit("libproc_macro2-1a2b.rlib", "proc-macro2-1a2b"));
+        assert!(belongs_to_unit("proc_macro2-1a2b.d", "proc-macro2-1a2b"));
+        assert!(!belongs_to_unit("libproc_macro-1a2b.rlib", "proc-macro2-1a2b"));
+    }
+
+    #[test]
+    fn test_other_toolchain_alone_trims_nothing() {
+        let target = std::env::temp_dir().join(format!("gigabroom-trim-{}", std::process::id()));
+        let unit = target.join("debug/.fingerprint/app-1a2b");
+        fs::create_dir_all(&unit).unwrap();
+        fs::write(unit.join("bin-app.json"), r#"{"rustc": 42}"#).unwrap();
+        fs::write(
+            target.join(".rustc_info.json"),
+            r#"{"outputs": {"1": {"stdout": "rustc 1.70.0 (90c541806 2023-05-31)\n"}}}"#,
+        )
+        .unwrap();
+
+        // The last build used another rustc, so recent units are kept
+        let report = trim_rust_target(&target, Duration::from_secs(86400), Some("rustc 1.80.0 (0)\n"), true);
+        assert_eq!(report.stale_toolchain_units, 0);
+        assert_eq!(report.stale_age_units, 0);
+
+        let _ = fs::remove_dir_all(&target);
+    }
+}
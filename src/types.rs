@@ -3,10 +3,12 @@
 //! Core data structures used throughout Gigabroom.
 //!
 //! This module defines the primary types for representing deletable items,
-//! categories of build artifacts, and scan cache data.
+//! categories of build artifacts, and the persistent scan index.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
 use std::time::SystemTime;
 
 /// Represents a deletable build artifact or cache directory.
@@ -41,6 +43,87 @@ pub struct DeletableItem {
     pub project_name: String,
     /// Last modification time
     pub last_modified: SystemTime,
+    /// Relationship to the enclosing git work tree, if any
+    #[serde(default)]
+    pub git: GitState,
+    /// Crates building into this item (shared Cargo targets only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_by: Vec<String>,
+    /// Workspace the item was built for (Cargo targets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+    /// What is using the item, if a running process or build is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_use: Option<String>,
+    /// The `.gigabroom.toml` marking the item as kept, if one does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kept: Option<PathBuf>,
+    /// What detection found, when a `.gigabroom.toml` changed the category
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected: Option<Category>,
+}
+
+/// How git sees a deletable item.
+///
+/// Items containing tracked files are protected from deletion; items git
+/// ignores are high-confidence build artifacts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitState {
+    /// Not checked yet, or git could not be queried
+    #[default]
+    Unknown,
+    /// Not inside a git work tree
+    NotInRepo,
+    /// Inside a work tree, neither tracked nor ignored
+    Untracked,
+    /// Matched by the repository's ignore rules
+    Ignored,
+    /// Contains files tracked by git - never deleted unless forced
+    Tracked,
+    /// Inside a work tree, but git could not be queried - protected like `Tracked`
+    Unverified,
+}
+
+impl GitState {
+    /// Returns true if the item holds committed files, or might
+    pub const fn is_protected(&self) -> bool {
+        matches!(self, GitState::Tracked | GitState::Unverified)
+    }
+
+    /// Returns true if git confirms the item is a disposable artifact
+    pub const fn is_ignored(&self) -> bool {
+        matches!(self, GitState::Ignored)
+    }
+}
+
+/// What happens to items selected for deletion
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteMode {
+    /// Remove permanently
+    #[default]
+    Delete,
+    /// Move into the XDG trash, restorable with `gigabroom restore`
+    Trash,
+    /// Rename into `.gigabroom-quarantine/`, deleted later by `gigabroom purge`
+    Quarantine,
+}
+
+impl DeleteMode {
+    /// Progressive verb shown while items are processed
+    pub const fn verb(&self) -> &'static str {
+        match self {
+            DeleteMode::Delete => "Deleting",
+            DeleteMode::Trash => "Moving to trash",
+            DeleteMode::Quarantine => "Quarantining",
+        }
+    }
+
+    /// Returns true if the items can be brought back afterwards
+    pub const fn is_undoable(&self) -> bool {
+        !matches!(self, DeleteMode::Delete)
+    }
 }
 
 /// Categories of build artifacts and caches that Gigabroom can detect.
@@ -94,14 +177,141 @@ pub enum Category {
 
     // General
     BuildCache,
+
+    // Any directory tagged with a CACHEDIR.TAG
+    TaggedCache,
+
+    // User-defined rules
+    Custom(CustomCategory),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ScanCache {
-    pub scan_path: PathBuf,
-    pub scan_time: SystemTime,
-    pub items: Vec<DeletableItem>,
-    pub max_depth: usize,
+/// A category declared by a user-defined detection rule.
+///
+/// Labels are interned so the category stays `Copy` like the built-in ones.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Hash)]
+pub struct CustomCategory {
+    /// Display name of the category
+    pub label: &'static str,
+    /// Whether deleting items in this category loses data
+    pub dangerous: bool,
+}
+
+impl CustomCategory {
+    pub fn new(label: &str, dangerous: bool) -> Self {
+        Self {
+            label: intern_label(label),
+            dangerous,
+        }
+    }
+}
+
+/// Intern a custom category label, returning a `'static` reference to it
+fn intern_label(label: &str) -> &'static str {
+    static LABELS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut labels = LABELS.get_or_init(Default::default).lock().unwrap();
+    if let Some(existing) = labels.get(label) {
+        return existing;
+    }
+
+    let leaked: &'static str = Box::leak(label.to_string().into_boxed_str());
+    labels.insert(leaked);
+    leaked
+}
+
+impl<'de> Deserialize<'de> for CustomCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Owned {
+            label: String,
+            dangerous: bool,
+        }
+
+        let owned = Owned::deserialize(deserializer)?;
+        Ok(Self::new(&owned.label, owned.dangerous))
+    }
+}
+
+/// Timestamp used when filtering items by age.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AgeSource {
+    /// Modification time of the artifact itself
+    #[default]
+    Mtime,
+    /// Last access time of the artifact itself
+    Atime,
+    /// Newest source file in the owning project (ignores artifacts)
+    Project,
+}
+
+impl AgeSource {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            AgeSource::Mtime => "artifact modified",
+            AgeSource::Atime => "artifact accessed",
+            AgeSource::Project => "project activity",
+        }
+    }
+}
+
+/// Order in which `clean --free` picks items within a safety level.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, clap::ValueEnum)]
+pub enum FreePolicy {
+    /// Least recently modified first
+    Oldest,
+    /// Biggest first (fewest deletions)
+    Largest,
+    /// Old and big items first, weighing age and size together
+    #[default]
+    Score,
+}
+
+impl FreePolicy {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            FreePolicy::Oldest => "oldest first",
+            FreePolicy::Largest => "largest first",
+            FreePolicy::Score => "age and size score",
+        }
+    }
+}
+
+/// Persistent scan index, keyed by absolute path
+///
+/// Directory listings are reused while a directory's mtime is unchanged, and
+/// artifact sizes while no directory inside the artifact has changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScanIndex {
+    pub version: u32,
+    /// Modification time of the rules file the listings were classified with
+    pub rules_stamp: Option<SystemTime>,
+    pub dirs: HashMap<PathBuf, CachedDir>,
+    pub sizes: HashMap<PathBuf, CachedSize>,
+}
+
+/// What a directory contained when it was last listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDir {
+    pub mtime: SystemTime,
+    /// When the listing was last confirmed to be current
+    pub verified: SystemTime,
+    /// Subdirectories (re-checked on every scan, since detection can depend on their contents)
+    pub subdirs: Vec<String>,
+    /// Deletable entries that aren't directories
+    pub artifact_files: Vec<(String, Category)>,
+    pub cargo_manifest: bool,
+    /// Whether the directory has a `.gigabroomignore`
+    pub ignore_file: bool,
+}
+
+/// Size of a deletable directory
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CachedSize {
+    /// Newest mtime of any directory inside the artifact
+    pub signature: SystemTime,
+    pub verified: SystemTime,
+    pub size: u64,
 }
 
 impl Category {
@@ -123,6 +333,8 @@ impl Category {
             Category::TempFiles => "Temp/log files",
             Category::PackageCache => "Package cache",
             Category::BuildCache => "Build cache",
+            Category::TaggedCache => "Tagged cache",
+            Category::Custom(custom) => custom.label,
         }
     }
 
@@ -144,12 +356,17 @@ impl Category {
             Category::TempFiles,
             Category::PackageCache,
             Category::BuildCache,
+            Category::TaggedCache,
         ]
     }
 
     /// Returns true if this category is dangerous to delete (loses data)
     pub const fn is_dangerous(&self) -> bool {
-        matches!(self, Category::PackageCache)
+        match self {
+            Category::PackageCache => true,
+            Category::Custom(custom) => custom.dangerous,
+            _ => false,
+        }
     }
 }
 
@@ -162,6 +379,12 @@ impl DeletableItem {
             category,
             project_name,
             last_modified,
+            git: GitState::Unknown,
+            shared_by: Vec::new(),
+            in_use: None,
+            kept: None,
+            detected: None,
+            workspace: None,
         }
     }
 }
@@ -64,7 +64,7 @@ fn visual_len(s: &str) -> usize {
     let mut width = 0;
     for c in stripped.chars() {
         // Skip zero-width characters
-        if c >= '\u{200B}' && c <= '\u{200D}' {
+        if ('\u{200B}'..='\u{200D}').contains(&c) {
             // Zero-width space, ZWSP, ZWJ
             continue;
         }
@@ -259,51 +259,111 @@ pub fn show_breadcrumb(steps: &[&str]) {
     println!("{}", "─".repeat(80).bright_black());
 }
 
-/// Preset profile definitions
-#[allow(dead_code)]
+/// Preset profile definitions: categories, filters and roots cleaned in one go
 #[derive(Debug, Clone)]
 pub struct CleanPreset {
-    pub name: &'static str,
-    pub emoji: &'static str,
-    pub description: &'static str,
+    /// Short name used with `--preset`
+    pub key: String,
+    pub name: String,
+    pub emoji: String,
+    pub description: String,
     pub categories: Vec<crate::types::Category>,
+    /// Default `--min-size` for this preset
+    pub min_size: Option<String>,
+    /// Default `--older-than` for this preset
+    pub older_than: Option<String>,
+    /// Directories cleaned when none are given (the configured roots if empty)
+    pub roots: Vec<String>,
     pub safety: SafetyLevel,
-    pub estimated_gb: &'static str,
+    pub estimated_gb: Option<String>,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SafetyLevel {
     Safe,
     Moderate,
     Dangerous,
 }
 
-#[allow(dead_code)]
+/// Categories of the Quick Clean preset, which are also what counts as safe
+const QUICK_CATEGORIES: [crate::types::Category; 5] = [
+    crate::types::Category::RustTarget,
+    crate::types::Category::NodeModules,
+    crate::types::Category::PythonCache,
+    crate::types::Category::IDECache,
+    crate::types::Category::OSJunk,
+];
+
+impl SafetyLevel {
+    /// How safe it is to delete a category: the Quick Clean categories are
+    /// safe, categories that lose data are dangerous, the rest moderate
+    pub fn of(category: crate::types::Category) -> Self {
+        if category.is_dangerous() {
+            SafetyLevel::Dangerous
+        } else if QUICK_CATEGORIES.contains(&category) {
+            SafetyLevel::Safe
+        } else {
+            SafetyLevel::Moderate
+        }
+    }
+
+    /// The riskiest level among some categories (Safe if there are none)
+    pub fn of_all(categories: impl IntoIterator<Item = crate::types::Category>) -> Self {
+        categories.into_iter().map(Self::of).max().unwrap_or(SafetyLevel::Safe)
+    }
+
+    pub const fn label(&self) -> &'static str {
+        match self {
+            SafetyLevel::Safe => "Safe",
+            SafetyLevel::Moderate => "Moderate",
+            SafetyLevel::Dangerous => "Dangerous",
+        }
+    }
+}
+
 impl CleanPreset {
-    pub fn quick_clean() -> Self {
+    fn builtin(
+        key: &str,
+        name: &str,
+        emoji: &str,
+        description: &str,
+        categories: Vec<crate::types::Category>,
+        safety: SafetyLevel,
+        estimated_gb: &str,
+    ) -> Self {
         Self {
-            name: "Quick Clean",
-            emoji: "🚀",
-            description: "Safe, common build artifacts",
-            categories: vec![
-                crate::types::Category::RustTarget,
-                crate::types::Category::NodeModules,
-                crate::types::Category::PythonCache,
-                crate::types::Category::IDECache,
-                crate::types::Category::OSJunk,
-            ],
-            safety: SafetyLevel::Safe,
-            estimated_gb: "2-5 GB",
+            key: key.to_string(),
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+            description: description.to_string(),
+            categories,
+            min_size: None,
+            older_than: None,
+            roots: Vec::new(),
+            safety,
+            estimated_gb: Some(estimated_gb.to_string()),
         }
     }
 
+    pub fn quick_clean() -> Self {
+        Self::builtin(
+            "quick",
+            "Quick Clean",
+            "🚀",
+            "Safe, common build artifacts",
+            QUICK_CATEGORIES.to_vec(),
+            SafetyLevel::Safe,
+            "2-5 GB",
+        )
+    }
+
     pub fn deep_clean() -> Self {
-        Self {
-            name: "Deep Clean",
-            emoji: "🧹",
-            description: "All build artifacts + caches + temp",
-            categories: vec![
+        Self::builtin(
+            "deep",
+            "Deep Clean",
+            "🧹",
+            "All build artifacts + caches + temp",
+            vec![
                 crate::types::Category::RustTarget,
                 crate::types::Category::NodeModules,
                 crate::types::Category::PythonCache,
@@ -319,18 +379,20 @@ impl CleanPreset {
                 crate::types::Category::OSJunk,
                 crate::types::Category::TempFiles,
                 crate::types::Category::BuildCache,
+                crate::types::Category::TaggedCache,
             ],
-            safety: SafetyLevel::Moderate,
-            estimated_gb: "5-15 GB",
-        }
+            SafetyLevel::Moderate,
+            "5-15 GB",
+        )
     }
 
     pub fn nuclear_clean() -> Self {
-        Self {
-            name: "Nuclear Clean",
-            emoji: "⚠️",
-            description: "EVERYTHING including global package caches!",
-            categories: vec![
+        Self::builtin(
+            "nuclear",
+            "Nuclear Clean",
+            "⚠️",
+            "EVERYTHING including global package caches!",
+            vec![
                 crate::types::Category::RustTarget,
                 crate::types::Category::NodeModules,
                 crate::types::Category::PythonCache,
@@ -347,16 +409,29 @@ impl CleanPreset {
                 crate::types::Category::TempFiles,
                 crate::types::Category::PackageCache,
                 crate::types::Category::BuildCache,
+                crate::types::Category::TaggedCache,
             ],
-            safety: SafetyLevel::Dangerous,
-            estimated_gb: "10-30 GB",
-        }
+            SafetyLevel::Dangerous,
+            "10-30 GB",
+        )
     }
 
-    pub fn all_presets() -> Vec<Self> {
+    pub fn builtin_presets() -> Vec<Self> {
         vec![Self::quick_clean(), Self::deep_clean(), Self::nuclear_clean()]
     }
 
+    /// Built-in presets followed by the user's own from the config file
+    pub fn all_presets() -> Vec<Self> {
+        let mut presets = Self::builtin_presets();
+        presets.extend(crate::config::get().presets());
+        presets
+    }
+
+    /// Look up a preset by its short name: quick, deep, nuclear or a user preset
+    pub fn named(name: &str) -> Option<Self> {
+        Self::all_presets().into_iter().find(|preset| preset.key.eq_ignore_ascii_case(name))
+    }
+
     pub fn display_name(&self) -> String {
         let safety_indicator = match self.safety {
             SafetyLevel::Safe => "✓".green(),
@@ -364,13 +439,18 @@ impl CleanPreset {
             SafetyLevel::Dangerous => "⚠⚠".red(),
         };
 
+        let estimate = match &self.estimated_gb {
+            Some(estimate) => format!(" ({})", estimate),
+            None => String::new(),
+        };
+
         format!(
-            "{} {} {} - {} ({})",
+            "{} {} {} - {}{}",
             self.emoji,
             self.name,
             safety_indicator,
             self.description,
-            self.estimated_gb
+            estimate
         )
     }
 }
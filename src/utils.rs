@@ -1,5 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::env;
+use std::fs;
+use std::time::Duration;
 
 /// Macro for conditional printing based on quiet flag
 #[macro_export]
@@ -15,7 +17,7 @@ macro_rules! println_unless_quiet {
 #[macro_export]
 macro_rules! print_error {
     ($($arg:tt)*) => {
-        eprintln!("{} {}", "Error:".bright_red().bold(), format!($($arg)*));
+        eprintln!("{} {}", "Error:".bright_red().bold(), format!($($arg)*))
     };
 }
 
@@ -23,7 +25,7 @@ macro_rules! print_error {
 #[macro_export]
 macro_rules! print_success {
     ($($arg:tt)*) => {
-        println!("{}", format!($($arg)*).green().bold());
+        println!("{}", format!($($arg)*).green().bold())
     };
 }
 
@@ -31,7 +33,7 @@ macro_rules! print_success {
 #[macro_export]
 macro_rules! print_warning {
     ($($arg:tt)*) => {
-        println!("{}", format!($($arg)*).yellow());
+        println!("{}", format!($($arg)*).yellow())
     };
 }
 
@@ -86,6 +88,34 @@ pub fn parse_size(size_str: &str) -> Result<u64, String> {
         .map_err(|_| format!("Invalid size format: {}", size_str))
 }
 
+/// Parse duration string (e.g., "30d", "2w", "6m", "1y") to a `Duration`
+///
+/// Months are 30 days and years are 365 days. A bare number means days.
+pub fn parse_duration(duration_str: &str) -> Result<Duration, String> {
+    const DAY: u64 = 24 * 60 * 60;
+
+    let duration_str = duration_str.trim().to_lowercase();
+
+    let (num_str, multiplier) = if let Some(num) = duration_str.strip_suffix('y') {
+        (num, 365 * DAY)
+    } else if let Some(num) = duration_str.strip_suffix('m') {
+        (num, 30 * DAY)
+    } else if let Some(num) = duration_str.strip_suffix('w') {
+        (num, 7 * DAY)
+    } else if let Some(num) = duration_str.strip_suffix('d') {
+        (num, DAY)
+    } else {
+        // Assume days if no suffix
+        (duration_str.as_str(), DAY)
+    };
+
+    num_str
+        .trim()
+        .parse::<u64>()
+        .map(|n| Duration::from_secs(n.saturating_mul(multiplier)))
+        .map_err(|_| format!("Invalid duration format: {}", duration_str))
+}
+
 /// Get project name from path
 #[inline]
 pub fn get_project_name(path: &Path) -> String {
@@ -96,12 +126,82 @@ pub fn get_project_name(path: &Path) -> String {
         .unwrap_or_else(|| "Unknown".to_string())
 }
 
+/// Signature every valid CACHEDIR.TAG must start with (https://bford.info/cachedir/)
+const CACHEDIR_TAG_SIGNATURE: &[u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+/// Read a directory's CACHEDIR.TAG if it carries a valid signature
+pub fn read_cachedir_tag(dir: &Path) -> Option<String> {
+    let contents = fs::read(dir.join("CACHEDIR.TAG")).ok()?;
+    if !contents.starts_with(CACHEDIR_TAG_SIGNATURE) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&contents).into_owned())
+}
+
+/// Check if a directory is marked as a cache by a valid CACHEDIR.TAG
+#[inline]
+pub fn has_cachedir_tag(dir: &Path) -> bool {
+    read_cachedir_tag(dir).is_some()
+}
+
+/// Check if a directory holds something cargo wrote: its CACHEDIR.TAG or
+/// the `.rustc_info.json` it keeps at the top of every target directory
+pub fn has_cargo_marker(path: &Path) -> bool {
+    path.join(".rustc_info.json").is_file()
+        || read_cachedir_tag(path)
+            .map(|tag| tag.contains("created by cargo"))
+            .unwrap_or(false)
+}
+
 /// Check if a path is a Cargo target directory
+///
+/// The parent must have a `Cargo.toml`, and the directory itself must carry
+/// one of cargo's markers, so a hand-made `target/` next to a manifest isn't
+/// mistaken for build output.
 #[inline]
 pub fn is_cargo_target(path: &Path) -> bool {
-    path.parent()
+    let beside_manifest = path.parent()
         .map(|p| p.join("Cargo.toml").exists())
-        .unwrap_or(false)
+        .unwrap_or(false);
+
+    beside_manifest && has_cargo_marker(path)
+}
+
+/// Resolve an XDG base directory, falling back to a path under the home directory
+fn xdg_base_dir(var: &str, home_fallback: &str) -> PathBuf {
+    env::var(var)
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var("HOME")
+                .or_else(|_| env::var("USERPROFILE"))
+                .ok()
+                .map(|home| PathBuf::from(home).join(home_fallback))
+        })
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Directory holding gigabroom's configuration files
+///
+/// Uses `$XDG_CONFIG_HOME/gigabroom`, falling back to `~/.config/gigabroom`.
+pub fn config_dir() -> PathBuf {
+    xdg_config_home().join("gigabroom")
+}
+
+/// The user's configuration directory (`$XDG_CONFIG_HOME`, or `~/.config`)
+pub fn xdg_config_home() -> PathBuf {
+    xdg_base_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// The user's data directory (`$XDG_DATA_HOME`, or `~/.local/share`)
+pub fn xdg_data_home() -> PathBuf {
+    xdg_base_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// Directory holding gigabroom's own records (trash log, history)
+pub fn data_dir() -> PathBuf {
+    xdg_data_home().join("gigabroom")
 }
 
 /// Expand tilde (~) in path to home directory and handle escaped spaces
@@ -109,9 +209,9 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     // Remove escape characters (backslashes before spaces)
     let cleaned_path = path.replace("\\ ", " ");
 
-    if cleaned_path.starts_with("~/") {
+    if let Some(rest) = cleaned_path.strip_prefix("~/") {
         if let Ok(home) = env::var("HOME") {
-            return PathBuf::from(home).join(&cleaned_path[2..]);
+            return PathBuf::from(home).join(rest);
         }
     } else if cleaned_path == "~" {
         if let Ok(home) = env::var("HOME") {
@@ -121,6 +221,49 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(cleaned_path)
 }
 
+/// Device a path lives on (the path itself, not a symlink's target)
+pub fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::symlink_metadata(path).ok().map(|m| m.dev())
+}
+
+/// Highest ancestor of `path` that is still on device `dev` (its mount point)
+pub fn mount_top(path: &Path, dev: u64) -> PathBuf {
+    let mut top = path;
+    for dir in path.ancestors().skip(1) {
+        if device_of(dir) != Some(dev) {
+            break;
+        }
+        top = dir;
+    }
+    top.to_path_buf()
+}
+
+/// Create a directory only the current user can access
+pub fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    match fs::DirBuilder::new().recursive(true).mode(0o700).create(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && path.is_dir() => Ok(()),
+        result => result,
+    }
+}
+
+/// Absolute path of an item, resolving its parent but not the item itself
+pub fn absolute_path(path: &Path) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("{}: not a file or directory name", path.display()))?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(parent.join(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +278,18 @@ mod tests {
         assert_eq!(parse_size("100mb").unwrap(), 100 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_parse_duration() {
+        const DAY: u64 = 24 * 60 * 60;
+        assert_eq!(parse_duration("30d").unwrap(), Duration::from_secs(30 * DAY));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::from_secs(14 * DAY));
+        assert_eq!(parse_duration("6m").unwrap(), Duration::from_secs(180 * DAY));
+        assert_eq!(parse_duration("1Y").unwrap(), Duration::from_secs(365 * DAY));
+        assert_eq!(parse_duration("7").unwrap(), Duration::from_secs(7 * DAY));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("3h").is_err());
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(1024), "1.00 KB");
@@ -142,6 +297,32 @@ mod tests {
         assert_eq!(format_size(1536 * 1024 * 1024), "1.50 GB");
     }
 
+    #[test]
+    fn test_is_cargo_target() {
+        let dir = env::temp_dir().join(format!("gigabroom-cargo-target-{}", std::process::id()));
+        let target = dir.join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+
+        // A plain target/ next to a manifest isn't enough
+        assert!(!is_cargo_target(&target));
+
+        fs::write(target.join(".rustc_info.json"), "{}").unwrap();
+        assert!(is_cargo_target(&target));
+
+        fs::remove_file(target.join(".rustc_info.json")).unwrap();
+        let mut tag = CACHEDIR_TAG_SIGNATURE.to_vec();
+        tag.extend_from_slice(b"\n# This file is a cache directory tag created by cargo.\n");
+        fs::write(target.join("CACHEDIR.TAG"), tag).unwrap();
+        assert!(is_cargo_target(&target));
+
+        // Cargo's tag without a manifest beside it isn't a crate's target
+        fs::remove_file(dir.join("Cargo.toml")).unwrap();
+        assert!(!is_cargo_target(&target));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_expand_tilde() {
         // Test tilde expansion
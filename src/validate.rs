@@ -0,0 +1,194 @@
+//! # Validate Module
+//!
+//! Re-checks items immediately before they are removed.
+//!
+//! Scan results can come from the scan cache, and the interactive menu can sit
+//! open for a long time. By the time a deletion is confirmed a directory may
+//! have been replaced, swapped for a symlink, or filled with something that
+//! isn't build output, so every item is run through detection again and
+//! skipped if it no longer looks like what was scanned.
+
+use crate::scanner::{calculate_dir_size_parallel, is_deletable};
+use crate::types::{Category, DeletableItem};
+use crate::utils::{absolute_path, format_size, is_cargo_target};
+use rayon::prelude::*;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Growth below this is never suspicious, however small the item was (256 MB)
+const GROWTH_SLACK: u64 = 256 * 1024 * 1024;
+
+/// Why an item no longer matches what the scan found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The path doesn't exist anymore
+    Vanished,
+    /// Detection now says something else (or nothing)
+    Category(Category, Option<Category>),
+    /// The item is a symlink to somewhere outside the scan root
+    SymlinkOutside(PathBuf),
+    /// A parent directory was moved or swapped, and the item now lives elsewhere
+    OutsideRoot(PathBuf),
+    /// Much bigger than when it was scanned
+    Grown { scanned: u64, now: u64 },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Vanished => write!(f, "no longer exists"),
+            Mismatch::Category(was, Some(now)) => {
+                write!(f, "was {}, now detected as {}", was.name(), now.name())
+            }
+            Mismatch::Category(was, None) => write!(f, "was {}, no longer detected as deletable", was.name()),
+            Mismatch::SymlinkOutside(target) => {
+                write!(f, "symlink to {}, outside the scan root", target.display())
+            }
+            Mismatch::OutsideRoot(real) => write!(f, "now resolves to {}, outside the scan root", real.display()),
+            Mismatch::Grown { scanned, now } => {
+                write!(f, "grew from {} to {} since the scan", format_size(*scanned), format_size(*now))
+            }
+        }
+    }
+}
+
+/// Returns true if a directory grew enough to no longer be trusted:
+/// more than doubled, and by more than [`GROWTH_SLACK`]
+fn grew_hugely(scanned: u64, now: u64) -> bool {
+    now > scanned.saturating_mul(2) && now - scanned > GROWTH_SLACK
+}
+
+/// Check that an item is still what the scan found
+///
+/// `root` is the directory that was scanned. Items outside it are only
+/// expected for Rust targets Cargo was configured to put elsewhere; those
+/// must still resolve to exactly the path that was scanned. Detection is
+/// compared with what it found at scan time, before any project override.
+pub fn revalidate(item: &DeletableItem, root: &Path) -> Result<(), Mismatch> {
+    let scanned = item.detected.unwrap_or(item.category);
+    let metadata = fs::symlink_metadata(&item.path).map_err(|_| Mismatch::Vanished)?;
+    let canonical_root = root.canonicalize().map_err(|_| Mismatch::Vanished)?;
+
+    // Where the item really is, resolving every parent but not the item itself
+    let real = absolute_path(&item.path).map_err(|_| Mismatch::Vanished)?;
+    let scanned_inside = std::path::absolute(&item.path)
+        .ok()
+        .zip(std::path::absolute(root).ok())
+        .is_some_and(|(path, root)| path.starts_with(root));
+
+    if scanned_inside {
+        if !real.starts_with(&canonical_root) {
+            return Err(Mismatch::OutsideRoot(real));
+        }
+    } else if scanned != Category::RustTarget || real != item.path {
+        return Err(Mismatch::OutsideRoot(real));
+    }
+
+    if metadata.file_type().is_symlink() {
+        // Removing a link never touches its target, but only links the scan
+        // could have followed are expected here
+        if let Ok(target) = item.path.canonicalize() {
+            if !target.starts_with(&canonical_root) {
+                return Err(Mismatch::SymlinkOutside(target));
+            }
+        }
+    }
+
+    let detected = is_deletable(&item.path);
+    let same_category = detected == Some(scanned)
+        || (scanned == Category::RustTarget && is_cargo_target(&item.path));
+    if !same_category {
+        return Err(Mismatch::Category(scanned, detected));
+    }
+
+    if metadata.is_dir() {
+        let now = calculate_dir_size_parallel(&item.path);
+        if grew_hugely(item.size, now) {
+            return Err(Mismatch::Grown {
+                scanned: item.size,
+                now,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The scanned root an item was found under (the first root if none contains it)
+fn root_of<'a>(item: &DeletableItem, roots: &'a [PathBuf]) -> &'a Path {
+    let path = std::path::absolute(&item.path).unwrap_or_else(|_| item.path.clone());
+    roots
+        .iter()
+        .find(|root| std::path::absolute(root).is_ok_and(|root| path.starts_with(root)))
+        .or(roots.first())
+        .map_or(Path::new("."), PathBuf::as_path)
+}
+
+/// Re-check the selected items, splitting them into those still safe to
+/// remove and those that changed since the scan
+pub fn revalidate_selection(
+    items: &[DeletableItem],
+    indices: &[usize],
+    roots: &[PathBuf],
+) -> (Vec<usize>, Vec<(usize, Mismatch)>) {
+    let checked: Vec<(usize, Result<(), Mismatch>)> = indices
+        .par_iter()
+        .filter_map(|&idx| items.get(idx).map(|item| (idx, revalidate(item, root_of(item, roots)))))
+        .collect();
+
+    let mut valid = Vec::new();
+    let mut changed = Vec::new();
+    for (idx, result) in checked {
+        match result {
+            Ok(()) => valid.push(idx),
+            Err(mismatch) => changed.push((idx, mismatch)),
+        }
+    }
+
+    (valid, changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_grew_hugely() {
+        assert!(!grew_hugely(1024, 10 * 1024 * 1024));
+        assert!(!grew_hugely(1 << 30, (1 << 30) + GROWTH_SLACK + 1));
+        assert!(grew_hugely(1024, GROWTH_SLACK * 2));
+    }
+
+    #[test]
+    fn test_revalidate() {
+        let root = std::env::temp_dir().join(format!("gigabroom-validate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let modules = root.join("app/node_modules");
+        fs::create_dir_all(&modules).unwrap();
+        fs::write(root.join("app/package.json"), "{}").unwrap();
+
+        let item = DeletableItem::new(modules.clone(), 0, Category::NodeModules, "app".to_string(), SystemTime::now());
+        assert_eq!(revalidate(&item, &root), Ok(()));
+
+        // Swapped for a symlink to somewhere else entirely
+        fs::remove_dir(&modules).unwrap();
+        std::os::unix::fs::symlink("/usr", &modules).unwrap();
+        assert!(matches!(revalidate(&item, &root), Err(Mismatch::SymlinkOutside(_))));
+
+        fs::remove_file(&modules).unwrap();
+        assert_eq!(revalidate(&item, &root), Err(Mismatch::Vanished));
+
+        // A project's .gigabroom.toml recategorized it
+        let out = root.join("app/out");
+        fs::create_dir_all(&out).unwrap();
+        fs::write(root.join("app/.gigabroom.toml"), "[category]\n\"out\" = \"temp\"\n").unwrap();
+        let mut items = vec![DeletableItem::new(out, 0, Category::BuildCache, "app".to_string(), SystemTime::now())];
+        crate::project::apply(&mut items, std::slice::from_ref(&root));
+        assert_eq!(items[0].category, Category::TempFiles);
+        assert_eq!(revalidate(&items[0], &root), Ok(()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
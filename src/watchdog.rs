@@ -0,0 +1,150 @@
+//! # Watchdog Module
+//!
+//! Keeps filesystems above a free-space threshold for `gigabroom watchdog`.
+//!
+//! Each check looks at the filesystem every watched root lives on. Only when
+//! one is below the threshold is its root scanned and cleaned, and only as
+//! much as it takes to get back above it.
+
+use crate::disk::{self, FsUsage};
+use crate::utils::{device_of, format_size, mount_top, parse_duration, parse_size};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How much space must stay free
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinFree {
+    /// Percentage of the filesystem's size
+    Percent(f64),
+    Bytes(u64),
+}
+
+impl MinFree {
+    /// Parse "15%" or a size like "20GB"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => match percent.trim().parse::<f64>() {
+                Ok(p) if (0.0..=100.0).contains(&p) => Ok(MinFree::Percent(p)),
+                _ => Err(format!("Invalid percentage: {}", s)),
+            },
+            None => parse_size(s).map(MinFree::Bytes),
+        }
+    }
+
+    /// Bytes that must be available on a filesystem of `total` bytes
+    pub fn required(&self, total: u64) -> u64 {
+        match *self {
+            MinFree::Percent(p) => (total as f64 * p / 100.0) as u64,
+            MinFree::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl fmt::Display for MinFree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinFree::Percent(p) => write!(f, "{}%", p),
+            MinFree::Bytes(bytes) => f.write_str(&format_size(*bytes)),
+        }
+    }
+}
+
+/// Shortest check interval; rescanning more often than this just burns CPU
+pub const MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Parse a check interval: "30s", "15min", "1h", or anything `parse_duration` takes
+///
+/// Intervals shorter than [`MIN_INTERVAL`] are rejected.
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    let s = s.trim().to_lowercase();
+    let (num, unit) = if let Some(num) = s.strip_suffix("min") {
+        (num, 60)
+    } else if let Some(num) = s.strip_suffix('h') {
+        (num, 60 * 60)
+    } else if let Some(num) = s.strip_suffix('s') {
+        (num, 1)
+    } else {
+        return parse_duration(&s).and_then(at_least_min);
+    };
+
+    num.trim()
+        .parse::<u64>()
+        .map(|n| Duration::from_secs(n.saturating_mul(unit)))
+        .map_err(|_| format!("Invalid interval format: {}", s))
+        .and_then(at_least_min)
+}
+
+fn at_least_min(interval: Duration) -> Result<Duration, String> {
+    if interval < MIN_INTERVAL {
+        return Err(format!("Interval must be at least {}", format_interval(MIN_INTERVAL)));
+    }
+    Ok(interval)
+}
+
+/// Format an interval the way [`parse_interval`] reads it
+pub fn format_interval(interval: Duration) -> String {
+    match interval.as_secs() {
+        secs if secs >= 86400 && secs % 86400 == 0 => format!("{}d", secs / 86400),
+        secs if secs >= 3600 && secs % 3600 == 0 => format!("{}h", secs / 3600),
+        secs if secs >= 60 && secs % 60 == 0 => format!("{}min", secs / 60),
+        secs => format!("{}s", secs),
+    }
+}
+
+/// Free space on the filesystem a watched root lives on
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub mount: PathBuf,
+    pub device: u64,
+    pub usage: FsUsage,
+    pub required: u64,
+}
+
+impl Check {
+    /// Look at the filesystem `root` is on
+    pub fn of(root: &Path, min_free: MinFree) -> Option<Self> {
+        let root = root.canonicalize().ok()?;
+        let device = device_of(&root)?;
+        let mount = mount_top(&root, device);
+        let usage = disk::usage(&mount)?;
+
+        Some(Self {
+            required: min_free.required(usage.total),
+            mount,
+            device,
+            usage,
+        })
+    }
+
+    /// Bytes to free to get back to the threshold (0 when above it)
+    pub fn deficit(&self) -> u64 {
+        self.required.saturating_sub(self.usage.available)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_free() {
+        assert_eq!(MinFree::parse("15%"), Ok(MinFree::Percent(15.0)));
+        assert_eq!(MinFree::parse("2GB"), Ok(MinFree::Bytes(2 * 1024 * 1024 * 1024)));
+        assert!(MinFree::parse("150%").is_err());
+
+        assert_eq!(MinFree::Percent(15.0).required(1000), 150);
+        assert_eq!(MinFree::Bytes(10).required(1000), 10);
+
+        assert_eq!(parse_interval("15min"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_interval("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_interval("1d"), Ok(Duration::from_secs(86400)));
+        assert_eq!(format_interval(Duration::from_secs(900)), "15min");
+
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("0min").is_err());
+        assert!(parse_interval("29s").is_err());
+        assert_eq!(parse_interval("30s"), Ok(MIN_INTERVAL));
+    }
+}